@@ -5,6 +5,14 @@ extern crate cc;
 use std::env;
 
 fn main() {
+    if env::var_os("CARGO_FEATURE_C_BACKEND").is_some() {
+        compile_c_backend();
+    }
+
+    generate_bindings("gen/bindings.rs");
+}
+
+fn compile_c_backend() {
     let mut build = cc::Build::new();
 
     build.include("src");
@@ -13,7 +21,7 @@ fn main() {
     let source_files = ["vendor/spirv_reflect.c"];
 
     for source_file in &source_files {
-        build.file(&source_file);
+        build.file(source_file);
     }
 
     let target = env::var("TARGET").unwrap();
@@ -31,8 +39,6 @@ fn main() {
     }
 
     build.compile("spirv_reflect_cpp");
-
-    generate_bindings("gen/bindings.rs");
 }
 
 #[cfg(feature = "generate_bindings")]
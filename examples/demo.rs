@@ -27,7 +27,7 @@ fn main() {
                 );
                 if var.name == "input.Alpha" {
                     // Change alpha input variable location from 2 to 8
-                    module.change_input_variable_location(&var, 8).unwrap();
+                    module.change_input_variable_location(var, 8).unwrap();
                 }
             }
 
@@ -0,0 +1,339 @@
+extern crate serde_json;
+extern crate serde_yaml;
+#[macro_use]
+extern crate serde_derive;
+extern crate spirv_reflect;
+
+use spirv_reflect::types::*;
+use spirv_reflect::ShaderModule;
+use std::fmt::Write;
+use std::io::{self, Write as _};
+use std::process;
+
+const USAGE: &str =
+    "usage: spirv-reflect [--format yaml|json|text] [--entry-point NAME] [--stage STAGE] FILE...";
+
+#[derive(Copy, Clone, PartialEq)]
+enum Format {
+    Yaml,
+    Json,
+    Text,
+}
+
+struct Options {
+    format: Format,
+    entry_point: Option<String>,
+    stage: Option<ReflectShaderStageFlags>,
+    files: Vec<String>,
+}
+
+// Field names follow the `module` section of the upstream `spirv-reflect -y` output.
+#[derive(Serialize)]
+struct ModuleReport {
+    generator: ReflectGenerator,
+    entry_point_name: String,
+    entry_point_id: u32,
+    source_language: spirv::SourceLanguage,
+    source_language_version: u32,
+    spirv_execution_model: spirv::ExecutionModel,
+    shader_stage: ReflectShaderStageFlags,
+    descriptor_binding_count: usize,
+    descriptor_bindings: Vec<ReflectDescriptorBinding>,
+    descriptor_set_count: usize,
+    descriptor_sets: Vec<ReflectDescriptorSet>,
+    input_variable_count: usize,
+    input_variables: Vec<ReflectInterfaceVariable>,
+    output_variable_count: usize,
+    output_variables: Vec<ReflectInterfaceVariable>,
+    push_constant_count: usize,
+    push_constants: Vec<ReflectBlockVariable>,
+    specialization_constant_count: usize,
+    specialization_constants: Vec<ReflectSpecializationConstant>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    file: String,
+    module: ModuleReport,
+}
+
+fn parse_stage(name: &str) -> Option<ReflectShaderStageFlags> {
+    let stage = match name.to_lowercase().as_str() {
+        "vert" | "vertex" | "vs" => ReflectShaderStageFlags::VERTEX,
+        "tesc" | "hull" | "hs" => ReflectShaderStageFlags::TESSELLATION_CONTROL,
+        "tese" | "domain" | "ds" => ReflectShaderStageFlags::TESSELLATION_EVALUATION,
+        "geom" | "geometry" | "gs" => ReflectShaderStageFlags::GEOMETRY,
+        "frag" | "fragment" | "pixel" | "ps" => ReflectShaderStageFlags::FRAGMENT,
+        "comp" | "compute" | "cs" => ReflectShaderStageFlags::COMPUTE,
+        "task" | "as" => ReflectShaderStageFlags::TASK_EXT,
+        "mesh" | "ms" => ReflectShaderStageFlags::MESH_EXT,
+        "rgen" | "raygen" => ReflectShaderStageFlags::RAYGEN_BIT_NV,
+        "rahit" | "anyhit" => ReflectShaderStageFlags::ANY_HIT_BIT_NV,
+        "rchit" | "closesthit" => ReflectShaderStageFlags::CLOSEST_HIT_BIT_NV,
+        "rmiss" | "miss" => ReflectShaderStageFlags::MISS_BIT_NV,
+        "rint" | "intersection" => ReflectShaderStageFlags::INTERSECTION_BIT_NV,
+        "rcall" | "callable" => ReflectShaderStageFlags::CALLABLE_BIT_NV,
+        _ => return None,
+    };
+    Some(stage)
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        format: Format::Text,
+        entry_point: None,
+        stage: None,
+        files: Vec::new(),
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("missing value for {}", name))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Err(String::new()),
+            "-y" => options.format = Format::Yaml,
+            "--format" => {
+                options.format = match value(arg)?.as_str() {
+                    "yaml" => Format::Yaml,
+                    "json" => Format::Json,
+                    "text" => Format::Text,
+                    format => return Err(format!("unknown format: {}", format)),
+                }
+            }
+            "--entry-point" => options.entry_point = Some(value(arg)?),
+            "--stage" => {
+                let stage = value(arg)?;
+                options.stage =
+                    Some(parse_stage(&stage).ok_or_else(|| format!("unknown stage: {}", stage))?);
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option: {}", arg)),
+            _ => options.files.push(arg.clone()),
+        }
+    }
+    if options.files.is_empty() {
+        return Err("no input files".to_owned());
+    }
+    Ok(options)
+}
+
+fn reflect_file(file: &str, options: &Options) -> Result<Vec<Report>, String> {
+    let spv_data = std::fs::read(file).map_err(|err| err.to_string())?;
+    let module = ShaderModule::load_u8_data(&spv_data).map_err(|err| err.to_string())?;
+    let entry_points = module
+        .enumerate_entry_points()
+        .map_err(|err| err.to_string())?;
+    if let Some(ref name) = options.entry_point {
+        if !entry_points
+            .iter()
+            .any(|entry_point| &entry_point.name == name)
+        {
+            return Err(format!("entry point not found: {}", name));
+        }
+    }
+
+    let mut reports = Vec::new();
+    for entry_point in &entry_points {
+        if let Some(ref name) = options.entry_point {
+            if &entry_point.name != name {
+                continue;
+            }
+        }
+        if let Some(stage) = options.stage {
+            if !entry_point.shader_stage.intersects(stage) {
+                continue;
+            }
+        }
+        reports.push(Report {
+            file: file.to_owned(),
+            module: reflect_entry_point(&module, entry_point).map_err(|err| err.to_string())?,
+        });
+    }
+    Ok(reports)
+}
+
+fn reflect_entry_point(
+    module: &ShaderModule,
+    entry_point: &ReflectEntryPoint,
+) -> Result<ModuleReport, spirv_reflect::error::ReflectError> {
+    let name = Some(entry_point.name.as_str());
+    let descriptor_bindings = module.enumerate_descriptor_bindings(name)?;
+    let descriptor_sets = module.enumerate_descriptor_sets(name)?;
+    let input_variables = module.enumerate_input_variables(name)?;
+    let output_variables = module.enumerate_output_variables(name)?;
+    let push_constants = module.enumerate_push_constant_blocks(name)?;
+    let specialization_constants = module.enumerate_specialization_constants()?;
+    Ok(ModuleReport {
+        generator: module.get_generator(),
+        entry_point_name: entry_point.name.clone(),
+        entry_point_id: entry_point.id,
+        source_language: module.get_source_language(),
+        source_language_version: module.get_source_language_version(),
+        spirv_execution_model: entry_point.spirv_execution_model,
+        shader_stage: entry_point.shader_stage,
+        descriptor_binding_count: descriptor_bindings.len(),
+        descriptor_bindings,
+        descriptor_set_count: descriptor_sets.len(),
+        descriptor_sets,
+        input_variable_count: input_variables.len(),
+        input_variables,
+        output_variable_count: output_variables.len(),
+        output_variables,
+        push_constant_count: push_constants.len(),
+        push_constants,
+        specialization_constant_count: specialization_constants.len(),
+        specialization_constants,
+    })
+}
+
+fn write_variables(out: &mut String, title: &str, variables: &[ReflectInterfaceVariable]) {
+    writeln!(out, "  {}: {}", title, variables.len()).unwrap();
+    for (index, variable) in variables.iter().enumerate() {
+        writeln!(out, "    {}:", index).unwrap();
+        writeln!(out, "      spirv id  : {}", variable.spirv_id).unwrap();
+        if variable
+            .decoration_flags
+            .contains(ReflectDecorationFlags::BUILT_IN)
+        {
+            writeln!(out, "      location  : (built-in) {:?}", *variable.built_in).unwrap();
+        } else {
+            writeln!(out, "      location  : {}", variable.location).unwrap();
+        }
+        writeln!(out, "      type      : {:?}", variable.format).unwrap();
+        if !variable.semantic.is_empty() {
+            writeln!(out, "      semantic  : {}", variable.semantic).unwrap();
+        }
+        writeln!(out, "      name      : {}", variable.name).unwrap();
+    }
+}
+
+fn write_block(out: &mut String, block: &ReflectBlockVariable, indent: usize) {
+    writeln!(
+        out,
+        "{:indent$}{} (offset {}, size {}, padded size {})",
+        "",
+        block.name,
+        block.offset,
+        block.size,
+        block.padded_size,
+        indent = indent
+    )
+    .unwrap();
+    for member in &block.members {
+        write_block(out, member, indent + 2);
+    }
+}
+
+fn text_report(report: &Report) -> String {
+    let module = &report.module;
+    let mut out = String::new();
+    writeln!(out, "file            : {}", report.file).unwrap();
+    writeln!(out, "generator       : {:?}", module.generator).unwrap();
+    writeln!(out, "entry point     : {}", module.entry_point_name).unwrap();
+    writeln!(
+        out,
+        "source lang     : {:?} {}",
+        module.source_language, module.source_language_version
+    )
+    .unwrap();
+    writeln!(out, "shader stage    : {:?}", module.shader_stage).unwrap();
+    writeln!(out).unwrap();
+
+    writeln!(
+        out,
+        "  Descriptor bindings: {}",
+        module.descriptor_bindings.len()
+    )
+    .unwrap();
+    for (index, binding) in module.descriptor_bindings.iter().enumerate() {
+        writeln!(out, "    {}:", index).unwrap();
+        writeln!(out, "      binding : {}", binding.binding).unwrap();
+        writeln!(out, "      set     : {}", binding.set).unwrap();
+        writeln!(out, "      type    : {:?}", binding.descriptor_type).unwrap();
+        if binding.count > 1 {
+            writeln!(out, "      count   : {}", binding.count).unwrap();
+        }
+        writeln!(out, "      name    : {}", binding.name).unwrap();
+    }
+
+    writeln!(out, "  Push constants: {}", module.push_constants.len()).unwrap();
+    for block in &module.push_constants {
+        write_block(&mut out, block, 4);
+    }
+
+    write_variables(&mut out, "Input variables", &module.input_variables);
+    write_variables(&mut out, "Output variables", &module.output_variables);
+
+    writeln!(
+        out,
+        "  Specialization constants: {}",
+        module.specialization_constants.len()
+    )
+    .unwrap();
+    for constant in &module.specialization_constants {
+        writeln!(
+            out,
+            "    {}: {} = {:?}",
+            constant.constant_id, constant.name, constant.default_value
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(err) => {
+            if err.is_empty() {
+                println!("{}", USAGE);
+                return;
+            }
+            eprintln!("spirv-reflect: {}\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let mut failed = false;
+    let mut reports = Vec::new();
+    for file in &options.files {
+        match reflect_file(file, &options) {
+            Ok(file_reports) => reports.extend(file_reports),
+            Err(err) => {
+                eprintln!("spirv-reflect: {}: {}", file, err);
+                failed = true;
+            }
+        }
+    }
+
+    let mut out = String::new();
+    match options.format {
+        Format::Yaml => {
+            for report in &reports {
+                writeln!(out, "{}", serde_yaml::to_string(report).unwrap()).unwrap();
+            }
+        }
+        Format::Json => {
+            writeln!(out, "{}", serde_json::to_string_pretty(&reports).unwrap()).unwrap()
+        }
+        Format::Text => {
+            for report in &reports {
+                writeln!(out, "{}", text_report(report)).unwrap();
+            }
+        }
+    }
+    // Output is commonly piped into `head` and friends, so a closed pipe is not an error.
+    if let Err(err) = io::stdout().write_all(out.as_bytes()) {
+        if err.kind() != io::ErrorKind::BrokenPipe {
+            eprintln!("spirv-reflect: {}", err);
+            process::exit(1);
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,494 @@
+use crate::types::{ReflectBlockVariable, ReflectDecorationFlags, ReflectTypeFlags};
+use std::fmt;
+use std::fmt::Write;
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "macro",
+    "override", "priv", "typeof", "unsized", "virtual", "yield", "try",
+];
+
+struct RustType {
+    name: String,
+    size: u32,
+    align: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CodegenError {
+    OverlappingMember { name: String, offset: u32, end: u32 },
+}
+
+impl fmt::Display for CodegenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CodegenError::OverlappingMember { name, offset, end } => write!(
+                f,
+                "member {} at offset {} overlaps the previous member, which ends at {}",
+                name, offset, end
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CodegenError {}
+
+#[derive(Default)]
+struct Generator {
+    structs: Vec<(String, String)>,
+}
+
+// Generates `#[repr(C)]` structs for a uniform or push constant block, with explicit padding
+// fields so that every member lands on its reflected offset. Runtime arrays are left out, and
+// members of an unknown type become byte arrays of the reflected size. The output depends on
+// `bytemuck`, and on `half` (with its `bytemuck` feature) for 16-bit floats.
+pub fn generate_block_structs(
+    block: &ReflectBlockVariable,
+    name: &str,
+) -> Result<String, CodegenError> {
+    let mut generator = Generator::default();
+    generator.generate_struct(
+        &type_name(name),
+        &block.members,
+        block.offset,
+        Some(block.size.saturating_sub(block.offset)),
+    )?;
+
+    let mut output = String::new();
+    for (index, (_, definition)) in generator.structs.iter().enumerate() {
+        if index > 0 {
+            output.push('\n');
+        }
+        output.push_str(definition);
+    }
+    Ok(output)
+}
+
+impl Generator {
+    fn generate_struct(
+        &mut self,
+        name: &str,
+        members: &[ReflectBlockVariable],
+        base_offset: u32,
+        size: Option<u32>,
+    ) -> Result<RustType, CodegenError> {
+        let mut fields: Vec<(String, String)> = Vec::new();
+        let mut cursor = 0;
+        let mut align = 1;
+        let mut padding = 0;
+        for member in members {
+            let is_runtime_array = member
+                .type_description
+                .as_ref()
+                .is_some_and(|td| *td.op == spirv::Op::TypeRuntimeArray);
+            let offset = member.offset.saturating_sub(base_offset);
+            if is_runtime_array {
+                continue;
+            }
+            if offset < cursor {
+                return Err(CodegenError::OverlappingMember {
+                    name: member.name.clone(),
+                    offset: member.offset,
+                    end: base_offset + cursor,
+                });
+            }
+            if offset > cursor {
+                fields.push(padding_field(&mut padding, offset - cursor));
+            }
+            let field_type = self.member_type(name, member)?;
+            fields.push((field_name(&member.name), field_type.name));
+            cursor = offset + field_type.size;
+            align = align.max(field_type.align);
+        }
+
+        let end = round_up(size.unwrap_or(cursor).max(cursor), align);
+        if end > cursor {
+            fields.push(padding_field(&mut padding, end - cursor));
+        }
+        Ok(RustType {
+            name: self.add_struct(name, &fields, end),
+            size: end,
+            align,
+        })
+    }
+
+    fn member_type(
+        &mut self,
+        parent: &str,
+        member: &ReflectBlockVariable,
+    ) -> Result<RustType, CodegenError> {
+        let bytes = RustType {
+            name: format!("[u8; {}]", member.size),
+            size: member.size,
+            align: 1,
+        };
+        let type_description = match member.type_description {
+            Some(ref type_description) => type_description,
+            None => return Ok(bytes),
+        };
+        let type_flags = type_description.type_flags;
+        let numeric = &member.numeric;
+
+        let element = if type_flags.contains(ReflectTypeFlags::STRUCT) {
+            let name = if type_description.type_name.is_empty() {
+                format!("{}{}", parent, type_name(&member.name))
+            } else {
+                type_name(&type_description.type_name)
+            };
+            self.generate_struct(&name, &member.members, 0, None)?
+        } else {
+            let scalar =
+                match scalar_type(type_flags, numeric.scalar.width, numeric.scalar.signedness) {
+                    Some(scalar) => scalar,
+                    None => return Ok(bytes),
+                };
+            if type_flags.contains(ReflectTypeFlags::MATRIX) {
+                let matrix = &numeric.matrix;
+                let major_count = if member
+                    .decoration_flags
+                    .contains(ReflectDecorationFlags::ROW_MAJOR)
+                {
+                    matrix.row_count
+                } else {
+                    matrix.column_count
+                };
+                // `u32::is_multiple_of` needs Rust 1.87.
+                #[allow(clippy::manual_is_multiple_of)]
+                let uneven_stride = matrix.stride == 0 || matrix.stride % scalar.size != 0;
+                if uneven_stride {
+                    return Ok(bytes);
+                }
+                RustType {
+                    name: format!(
+                        "[[{}; {}]; {}]",
+                        scalar.name,
+                        matrix.stride / scalar.size,
+                        major_count
+                    ),
+                    size: major_count * matrix.stride,
+                    align: scalar.align,
+                }
+            } else if type_flags.contains(ReflectTypeFlags::VECTOR) {
+                RustType {
+                    name: format!("[{}; {}]", scalar.name, numeric.vector.component_count),
+                    size: numeric.vector.component_count * scalar.size,
+                    align: scalar.align,
+                }
+            } else {
+                scalar
+            }
+        };
+
+        if !type_flags.contains(ReflectTypeFlags::ARRAY) || member.array.dims.is_empty() {
+            return Ok(element);
+        }
+        let stride = member.array.stride;
+        let mut array = if stride > element.size {
+            let name = format!("{}{}Element", parent, type_name(&member.name));
+            let fields = vec![
+                ("value".to_owned(), element.name),
+                padding_field(&mut 0, stride - element.size),
+            ];
+            RustType {
+                name: self.add_struct(&name, &fields, stride),
+                size: stride,
+                align: element.align,
+            }
+        } else {
+            element
+        };
+        for &dim in member.array.dims.iter().rev() {
+            array = RustType {
+                name: format!("[{}; {}]", array.name, dim),
+                size: array.size * dim,
+                align: array.align,
+            };
+        }
+        Ok(array)
+    }
+
+    // Identical definitions (such as a struct type used by several members) are emitted once,
+    // while different layouts that share a name get a numeric suffix.
+    fn add_struct(&mut self, name: &str, fields: &[(String, String)], size: u32) -> String {
+        let mut suffix = 0;
+        loop {
+            let candidate = match suffix {
+                0 => name.to_owned(),
+                _ => format!("{}{}", name, suffix),
+            };
+            let definition = struct_definition(&candidate, fields, size);
+            match self
+                .structs
+                .iter()
+                .find(|(existing, _)| *existing == candidate)
+            {
+                Some((_, existing)) if *existing == definition => return candidate,
+                Some(_) => suffix += 1,
+                None => {
+                    self.structs.push((candidate.clone(), definition));
+                    return candidate;
+                }
+            }
+        }
+    }
+}
+
+fn struct_definition(name: &str, fields: &[(String, String)], size: u32) -> String {
+    let mut definition = String::new();
+    writeln!(definition, "#[repr(C)]").unwrap();
+    writeln!(definition, "#[derive(Clone, Copy)]").unwrap();
+    if fields.is_empty() {
+        writeln!(definition, "pub struct {} {{}}", name).unwrap();
+    } else {
+        writeln!(definition, "pub struct {} {{", name).unwrap();
+        for (field_name, field_type) in fields {
+            writeln!(definition, "    pub {}: {},", field_name, field_type).unwrap();
+        }
+        writeln!(definition, "}}").unwrap();
+    }
+    writeln!(definition).unwrap();
+    writeln!(
+        definition,
+        "unsafe impl bytemuck::Zeroable for {} {{}}",
+        name
+    )
+    .unwrap();
+    writeln!(definition, "unsafe impl bytemuck::Pod for {} {{}}", name).unwrap();
+    writeln!(
+        definition,
+        "const _: () = assert!(std::mem::size_of::<{}>() == {});",
+        name, size
+    )
+    .unwrap();
+    definition
+}
+
+fn scalar_type(type_flags: ReflectTypeFlags, width: u32, signedness: u32) -> Option<RustType> {
+    let name = if type_flags.contains(ReflectTypeFlags::FLOAT) {
+        match width {
+            16 => "half::f16",
+            32 => "f32",
+            64 => "f64",
+            _ => return None,
+        }
+    } else if type_flags.contains(ReflectTypeFlags::INT) {
+        match (width, signedness) {
+            (8, 0) => "u8",
+            (8, _) => "i8",
+            (16, 0) => "u16",
+            (16, _) => "i16",
+            (32, 0) => "u32",
+            (32, _) => "i32",
+            (64, 0) => "u64",
+            (64, _) => "i64",
+            _ => return None,
+        }
+    } else if type_flags.contains(ReflectTypeFlags::BOOL) {
+        return Some(RustType {
+            name: "u32".to_owned(),
+            size: 4,
+            align: 4,
+        });
+    } else {
+        return None;
+    };
+    Some(RustType {
+        name: name.to_owned(),
+        size: width / 8,
+        align: width / 8,
+    })
+}
+
+fn padding_field(padding: &mut u32, size: u32) -> (String, String) {
+    let field = (format!("_pad{}", padding), format!("[u8; {}]", size));
+    *padding += 1;
+    field
+}
+
+fn round_up(value: u32, align: u32) -> u32 {
+    value.div_ceil(align) * align
+}
+
+fn identifier(name: String) -> String {
+    let name = if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    };
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+fn field_name(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::new();
+    for (index, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            snake.push('_');
+            continue;
+        }
+        if c.is_ascii_uppercase() && index > 0 {
+            let previous = chars[index - 1];
+            let next_is_lower = chars.get(index + 1).is_some_and(|n| n.is_ascii_lowercase());
+            if previous.is_ascii_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_ascii_uppercase() && next_is_lower)
+            {
+                snake.push('_');
+            }
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    identifier(snake)
+}
+
+fn type_name(name: &str) -> String {
+    let mut camel = String::new();
+    for part in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            camel.push(first.to_ascii_uppercase());
+            camel.extend(chars);
+        }
+    }
+    identifier(camel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{block_member, float};
+    use crate::types::*;
+
+    fn member(
+        name: &str,
+        offset: u32,
+        size: u32,
+        type_flags: ReflectTypeFlags,
+        numeric: ReflectNumericTraits,
+    ) -> ReflectBlockVariable {
+        ReflectBlockVariable {
+            size,
+            padded_size: size,
+            ..block_member(name, offset, type_flags, numeric)
+        }
+    }
+
+    #[test]
+    fn std140_block() {
+        let vector = ReflectTypeFlags::FLOAT | ReflectTypeFlags::VECTOR;
+        let mut normal = member(
+            "normalMatrix",
+            0,
+            48,
+            vector | ReflectTypeFlags::MATRIX,
+            ReflectNumericTraits {
+                matrix: ReflectNumericTraitsMatrix {
+                    column_count: 3,
+                    row_count: 3,
+                    stride: 16,
+                },
+                ..float(3)
+            },
+        );
+        normal.decoration_flags = ReflectDecorationFlags::COLUMN_MAJOR;
+        let mut weights = member(
+            "weights",
+            64,
+            64,
+            ReflectTypeFlags::FLOAT | ReflectTypeFlags::ARRAY,
+            float(0),
+        );
+        weights.array = ReflectArrayTraits {
+            dims: vec![4],
+            stride: 16,
+        };
+        let block = ReflectBlockVariable {
+            name: "Globals".to_owned(),
+            size: 144,
+            padded_size: 144,
+            members: vec![
+                normal,
+                member("tint", 48, 12, vector, float(3)),
+                weights,
+                member("type", 128, 4, ReflectTypeFlags::FLOAT, float(0)),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            generate_block_structs(&block, "globals").unwrap(),
+            "#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct GlobalsWeightsElement {
+    pub value: f32,
+    pub _pad0: [u8; 12],
+}
+
+unsafe impl bytemuck::Zeroable for GlobalsWeightsElement {}
+unsafe impl bytemuck::Pod for GlobalsWeightsElement {}
+const _: () = assert!(std::mem::size_of::<GlobalsWeightsElement>() == 16);
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Globals {
+    pub normal_matrix: [[f32; 4]; 3],
+    pub tint: [f32; 3],
+    pub _pad0: [u8; 4],
+    pub weights: [GlobalsWeightsElement; 4],
+    pub type_: f32,
+    pub _pad1: [u8; 12],
+}
+
+unsafe impl bytemuck::Zeroable for Globals {}
+unsafe impl bytemuck::Pod for Globals {}
+const _: () = assert!(std::mem::size_of::<Globals>() == 144);
+"
+        );
+    }
+
+    #[test]
+    fn overlapping_member() {
+        let scalar = ReflectTypeFlags::FLOAT;
+        let block = ReflectBlockVariable {
+            size: 16,
+            members: vec![
+                member("a", 0, 12, scalar | ReflectTypeFlags::VECTOR, float(3)),
+                member("b", 8, 4, scalar, float(0)),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            generate_block_structs(&block, "overlap"),
+            Err(CodegenError::OverlappingMember {
+                name: "b".to_owned(),
+                offset: 8,
+                end: 12
+            })
+        );
+    }
+
+    #[test]
+    fn half_floats() {
+        let half = scalar_type(ReflectTypeFlags::FLOAT, 16, 0).unwrap();
+        assert_eq!(half.name, "half::f16");
+        assert_eq!((half.size, half.align), (2, 2));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(field_name("XformMatrix"), "xform_matrix");
+        assert_eq!(field_name("MVPMatrix"), "mvp_matrix");
+        assert_eq!(field_name("g_Color"), "g_color");
+        assert_eq!(field_name("@data"), "_data");
+        assert_eq!(field_name("self"), "self_");
+        assert_eq!(type_name("my_light"), "MyLight");
+        assert_eq!(type_name("RGB"), "RGB");
+        assert_eq!(type_name("2d"), "_2d");
+    }
+}
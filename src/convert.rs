@@ -1,8 +1,9 @@
+use crate::error::{ReflectError, ReflectErrorKind};
 use crate::ffi;
 use crate::types::*;
 use num_traits::cast::FromPrimitive;
 
-unsafe fn ffi_slice_from_raw_parts<'a, T>(data: *const T, len: usize) -> &'a [T] {
+pub(crate) unsafe fn ffi_slice_from_raw_parts<'a, T>(data: *const T, len: usize) -> &'a [T] {
     if len > 0 {
         std::slice::from_raw_parts(data, len)
     } else {
@@ -14,9 +15,8 @@ pub(crate) fn ffi_to_entry_point(ffi_type: &ffi::SpvReflectEntryPoint) -> Reflec
     ReflectEntryPoint {
         name: super::ffi_to_string(ffi_type.name),
         id: ffi_type.id,
-        spirv_execution_model: match spirv::ExecutionModel::from_u32(
-            ffi_type.spirv_execution_model as u32,
-        ) {
+        spirv_execution_model: match spirv::ExecutionModel::from_u32(ffi_type.spirv_execution_model)
+        {
             Some(model) => model,
             None => spirv::ExecutionModel::Vertex,
         },
@@ -64,6 +64,9 @@ pub(crate) fn ffi_to_entry_point(ffi_type: &ffi::SpvReflectEntryPoint) -> Reflec
             y: ffi_type.local_size.y,
             z: ffi_type.local_size.z,
         },
+        invocations: ffi_type.invocations,
+        output_vertices: ffi_type.output_vertices,
+        execution_modes: Vec::new(),
     }
 }
 
@@ -77,27 +80,38 @@ pub(crate) fn ffi_to_interface_variable(
         .iter()
         .map(|member| ffi_to_interface_variable(member))
         .collect();
+    let numeric = ffi_to_numeric_traits(ffi_type.numeric);
+    let type_description = if ffi_type.type_description.is_null() {
+        None
+    } else {
+        Some(ffi_to_type_description(unsafe {
+            &*ffi_type.type_description
+        }))
+    };
+    let format = match ffi_to_format(ffi_type.format) {
+        ReflectFormat::Undefined => match type_description {
+            Some(ref type_description) => {
+                ReflectFormat::from_numeric_traits(&numeric, type_description.type_flags)
+            }
+            None => ReflectFormat::Undefined,
+        },
+        format => format,
+    };
     ReflectInterfaceVariable {
         spirv_id: ffi_type.spirv_id,
         name: super::ffi_to_string(ffi_type.name),
         location: ffi_type.location,
+        component: ffi_type.component,
         storage_class: ffi_to_storage_class(ffi_type.storage_class),
         semantic: super::ffi_to_string(ffi_type.semantic),
         decoration_flags: ffi_to_decoration_flags(ffi_type.decoration_flags),
         built_in: ReflectBuiltIn::from(ffi_type.built_in),
-        numeric: ffi_to_numeric_traits(ffi_type.numeric),
+        numeric,
         array: ffi_to_array_traits(ffi_type.array),
         members,
-        format: ffi_to_format(ffi_type.format),
-        type_description: if ffi_type.type_description.is_null() {
-            None
-        } else {
-            Some(ffi_to_type_description(unsafe {
-                &*ffi_type.type_description
-            }))
-        },
+        format,
+        type_description,
         word_offset: ffi_type.word_offset.location,
-        internal_data: ffi_type_ptr,
     }
 }
 
@@ -106,10 +120,8 @@ pub(crate) fn ffi_to_type_description(
 ) -> ReflectTypeDescription {
     let ffi_members =
         unsafe { ffi_slice_from_raw_parts(ffi_type.members, ffi_type.member_count as usize) };
-    let members: Vec<ReflectTypeDescription> = ffi_members
-        .iter()
-        .map(|member| ffi_to_type_description(member))
-        .collect();
+    let members: Vec<ReflectTypeDescription> =
+        ffi_members.iter().map(ffi_to_type_description).collect();
     ReflectTypeDescription {
         id: ffi_type.id,
         op: ReflectOp::from(ffi_type.op),
@@ -137,7 +149,37 @@ pub(crate) fn ffi_to_descriptor_set(
     descriptor::ReflectDescriptorSet {
         set: ffi_type.set,
         bindings,
-        internal_data: ffi_type_ptr,
+    }
+}
+
+// Storage resources can be read and written unless the variable, the block or every member of
+// the block is decorated NonReadable or NonWritable. Everything else is read-only.
+pub(crate) fn descriptor_access(
+    descriptor_type: ReflectDescriptorType,
+    decoration_flags: ReflectDecorationFlags,
+    block: &ReflectBlockVariable,
+) -> ReflectAccess {
+    let decorated = |flag: ReflectDecorationFlags| {
+        decoration_flags.contains(flag)
+            || block.decoration_flags.contains(flag)
+            || (!block.members.is_empty()
+                && block
+                    .members
+                    .iter()
+                    .all(|member| member.decoration_flags.contains(flag)))
+    };
+    match descriptor_type {
+        ReflectDescriptorType::StorageBuffer
+        | ReflectDescriptorType::StorageBufferDynamic
+        | ReflectDescriptorType::StorageImage
+        | ReflectDescriptorType::StorageTexelBuffer => ReflectAccess {
+            read: !decorated(ReflectDecorationFlags::NON_READABLE),
+            write: !decorated(ReflectDecorationFlags::NON_WRITABLE),
+        },
+        _ => ReflectAccess {
+            read: true,
+            write: false,
+        },
     }
 }
 
@@ -145,18 +187,23 @@ pub(crate) fn ffi_to_descriptor_binding(
     ffi_type_ptr: *const ffi::SpvReflectDescriptorBinding,
 ) -> ReflectDescriptorBinding {
     let ffi_type = unsafe { &*ffi_type_ptr };
+    let descriptor_type = ffi_to_descriptor_type(ffi_type.descriptor_type);
+    let decoration_flags = ffi_to_decoration_flags(ffi_type.decoration_flags);
+    let block = ffi_to_block_variable(&ffi_type.block);
+    let access = descriptor_access(descriptor_type, decoration_flags, &block);
     ReflectDescriptorBinding {
         spirv_id: ffi_type.spirv_id,
         name: super::ffi_to_string(ffi_type.name),
         binding: ffi_type.binding,
         input_attachment_index: ffi_type.input_attachment_index,
         set: ffi_type.set,
-        descriptor_type: ffi_to_descriptor_type(ffi_type.descriptor_type),
+        descriptor_type,
         resource_type: ffi_to_resource_type(ffi_type.resource_type),
         image: ffi_to_image_traits(ffi_type.image),
-        block: ffi_to_block_variable(&ffi_type.block),
+        block,
         array: ffi_to_binding_array_traits(ffi_type.array),
         count: ffi_type.count,
+        accessed: ffi_type.accessed != 0,
         uav_counter_id: ffi_type.uav_counter_id,
         uav_counter_binding: if ffi_type.uav_counter_binding.is_null() {
             None
@@ -165,6 +212,13 @@ pub(crate) fn ffi_to_descriptor_binding(
                 ffi_type.uav_counter_binding,
             )))
         },
+        byte_address_buffer_offsets: unsafe {
+            ffi_slice_from_raw_parts(
+                ffi_type.byte_address_buffer_offsets,
+                ffi_type.byte_address_buffer_offset_count as usize,
+            )
+        }
+        .to_vec(),
         type_description: if ffi_type.type_description.is_null() {
             None
         } else {
@@ -173,7 +227,127 @@ pub(crate) fn ffi_to_descriptor_binding(
             }))
         },
         word_offset: (ffi_type.word_offset.binding, ffi_type.word_offset.set),
-        internal_data: ffi_type_ptr,
+        decoration_flags,
+        user_type: ffi_to_user_type(ffi_type.user_type),
+        access,
+    }
+}
+
+pub(crate) fn ffi_to_user_type(ffi_type: ffi::SpvReflectUserType) -> ReflectUserType {
+    match ffi_type {
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_INVALID => ReflectUserType::Invalid,
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_CBUFFER => ReflectUserType::Cbuffer,
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_TBUFFER => ReflectUserType::Tbuffer,
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_APPEND_STRUCTURED_BUFFER => {
+            ReflectUserType::AppendStructuredBuffer
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_BUFFER => ReflectUserType::Buffer,
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_BYTE_ADDRESS_BUFFER => {
+            ReflectUserType::ByteAddressBuffer
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_CONSTANT_BUFFER => {
+            ReflectUserType::ConstantBuffer
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_CONSUME_STRUCTURED_BUFFER => {
+            ReflectUserType::ConsumeStructuredBuffer
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_INPUT_PATCH => ReflectUserType::InputPatch,
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_OUTPUT_PATCH => ReflectUserType::OutputPatch,
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RASTERIZER_ORDERED_BUFFER => {
+            ReflectUserType::RasterizerOrderedBuffer
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RASTERIZER_ORDERED_BYTE_ADDRESS_BUFFER => {
+            ReflectUserType::RasterizerOrderedByteAddressBuffer
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RASTERIZER_ORDERED_STRUCTURED_BUFFER => {
+            ReflectUserType::RasterizerOrderedStructuredBuffer
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RASTERIZER_ORDERED_TEXTURE_1D => {
+            ReflectUserType::RasterizerOrderedTexture1d
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RASTERIZER_ORDERED_TEXTURE_1D_ARRAY => {
+            ReflectUserType::RasterizerOrderedTexture1dArray
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RASTERIZER_ORDERED_TEXTURE_2D => {
+            ReflectUserType::RasterizerOrderedTexture2d
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RASTERIZER_ORDERED_TEXTURE_2D_ARRAY => {
+            ReflectUserType::RasterizerOrderedTexture2dArray
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RASTERIZER_ORDERED_TEXTURE_3D => {
+            ReflectUserType::RasterizerOrderedTexture3d
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RAYTRACING_ACCELERATION_STRUCTURE => {
+            ReflectUserType::RaytracingAccelerationStructure
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RW_BUFFER => ReflectUserType::RwBuffer,
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RW_BYTE_ADDRESS_BUFFER => {
+            ReflectUserType::RwByteAddressBuffer
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RW_STRUCTURED_BUFFER => {
+            ReflectUserType::RwStructuredBuffer
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RW_TEXTURE_1D => ReflectUserType::RwTexture1d,
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RW_TEXTURE_1D_ARRAY => {
+            ReflectUserType::RwTexture1dArray
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RW_TEXTURE_2D => ReflectUserType::RwTexture2d,
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RW_TEXTURE_2D_ARRAY => {
+            ReflectUserType::RwTexture2dArray
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_RW_TEXTURE_3D => ReflectUserType::RwTexture3d,
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_STRUCTURED_BUFFER => {
+            ReflectUserType::StructuredBuffer
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_SUBPASS_INPUT => {
+            ReflectUserType::SubpassInput
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_SUBPASS_INPUT_MS => {
+            ReflectUserType::SubpassInputMs
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_TEXTURE_1D => ReflectUserType::Texture1d,
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_TEXTURE_1D_ARRAY => {
+            ReflectUserType::Texture1dArray
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_TEXTURE_2D => ReflectUserType::Texture2d,
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_TEXTURE_2D_ARRAY => {
+            ReflectUserType::Texture2dArray
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_TEXTURE_2DMS => ReflectUserType::Texture2dMs,
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_TEXTURE_2DMS_ARRAY => {
+            ReflectUserType::Texture2dMsArray
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_TEXTURE_3D => ReflectUserType::Texture3d,
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_TEXTURE_BUFFER => {
+            ReflectUserType::TextureBuffer
+        }
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_TEXTURE_CUBE => ReflectUserType::TextureCube,
+        ffi::SpvReflectUserType_SPV_REFLECT_USER_TYPE_TEXTURE_CUBE_ARRAY => {
+            ReflectUserType::TextureCubeArray
+        }
+        _ => ReflectUserType::Unknown(ffi_type),
+    }
+}
+
+pub(crate) fn ffi_to_specialization_constant(
+    ffi_type: &ffi::SpvReflectSpecializationConstant,
+    default: Option<&crate::parser::SpecializationConstantDefault>,
+) -> ReflectSpecializationConstant {
+    let default = default.copied().unwrap_or_default();
+    ReflectSpecializationConstant {
+        spirv_id: ffi_type.spirv_id,
+        constant_id: ffi_type.constant_id,
+        name: super::ffi_to_string(ffi_type.name),
+        type_flags: default.type_flags,
+        scalar: default.scalar,
+        default_value: default.value,
+    }
+}
+
+pub(crate) fn ffi_to_capability(ffi_type: &ffi::SpvReflectCapability) -> ReflectCapability {
+    ReflectCapability {
+        value: spirv::Capability::from_u32(ffi_type.value),
+        word_offset: ffi_type.word_offset,
     }
 }
 
@@ -253,7 +427,7 @@ pub(crate) fn ffi_to_descriptor_type(
         ffi::SpvReflectDescriptorType_SPV_REFLECT_DESCRIPTOR_TYPE_ACCELERATION_STRUCTURE_KHR => {
             ReflectDescriptorType::AccelerationStructureKHR
         }
-        _ => unimplemented!(),
+        _ => ReflectDescriptorType::Unknown(ffi_type),
     }
 }
 
@@ -279,7 +453,7 @@ pub(crate) fn ffi_to_resource_type(ffi_type: ffi::SpvReflectResourceType) -> Ref
         ffi::SpvReflectResourceType_SPV_REFLECT_RESOURCE_FLAG_UAV => {
             ReflectResourceType::UnorderedAccessView
         }
-        _ => unimplemented!(),
+        _ => ReflectResourceType::Unknown(ffi_type),
     }
 }
 
@@ -292,7 +466,8 @@ pub(crate) fn ffi_to_dimension(ffi_type: ffi::SpvDim) -> ReflectDimension {
         ffi::SpvDim__SpvDimRect => ReflectDimension::Rect,
         ffi::SpvDim__SpvDimBuffer => ReflectDimension::Buffer,
         ffi::SpvDim__SpvDimSubpassData => ReflectDimension::SubPassData,
-        _ => unimplemented!(),
+        ffi::SpvDim__SpvDimTileImageDataEXT => ReflectDimension::TileImageDataEXT,
+        _ => ReflectDimension::Unknown(ffi_type),
     }
 }
 
@@ -359,13 +534,35 @@ pub(crate) fn ffi_to_image_format(ffi_type: ffi::SpvImageFormat) -> ReflectImage
         ffi::SpvImageFormat__SpvImageFormatRg8ui => ReflectImageFormat::RG8_UINT,
         ffi::SpvImageFormat__SpvImageFormatR16ui => ReflectImageFormat::R16_UINT,
         ffi::SpvImageFormat__SpvImageFormatR8ui => ReflectImageFormat::R8_UINT,
-        _ => unimplemented!(),
+        ffi::SpvImageFormat__SpvImageFormatR64ui => ReflectImageFormat::R64_UINT,
+        ffi::SpvImageFormat__SpvImageFormatR64i => ReflectImageFormat::R64_SINT,
+        _ => ReflectImageFormat::Unknown(ffi_type),
     }
 }
 
 pub(crate) fn ffi_to_format(ffi_type: ffi::SpvReflectFormat) -> ReflectFormat {
     match ffi_type {
         ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_UNDEFINED => ReflectFormat::Undefined,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R16_UINT => ReflectFormat::R16_UINT,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R16_SINT => ReflectFormat::R16_SINT,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R16_SFLOAT => ReflectFormat::R16_SFLOAT,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R16G16_UINT => ReflectFormat::R16G16_UINT,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R16G16_SINT => ReflectFormat::R16G16_SINT,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R16G16_SFLOAT => ReflectFormat::R16G16_SFLOAT,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R16G16B16_UINT => ReflectFormat::R16G16B16_UINT,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R16G16B16_SINT => ReflectFormat::R16G16B16_SINT,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R16G16B16_SFLOAT => {
+            ReflectFormat::R16G16B16_SFLOAT
+        }
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R16G16B16A16_UINT => {
+            ReflectFormat::R16G16B16A16_UINT
+        }
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R16G16B16A16_SINT => {
+            ReflectFormat::R16G16B16A16_SINT
+        }
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R16G16B16A16_SFLOAT => {
+            ReflectFormat::R16G16B16A16_SFLOAT
+        }
         ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R32_UINT => ReflectFormat::R32_UINT,
         ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R32_SINT => ReflectFormat::R32_SINT,
         ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R32_SFLOAT => ReflectFormat::R32_SFLOAT,
@@ -386,12 +583,32 @@ pub(crate) fn ffi_to_format(ffi_type: ffi::SpvReflectFormat) -> ReflectFormat {
         ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R32G32B32A32_SFLOAT => {
             ReflectFormat::R32G32B32A32_SFLOAT
         }
-        _ => unimplemented!(),
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R64_UINT => ReflectFormat::R64_UINT,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R64_SINT => ReflectFormat::R64_SINT,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R64_SFLOAT => ReflectFormat::R64_SFLOAT,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R64G64_UINT => ReflectFormat::R64G64_UINT,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R64G64_SINT => ReflectFormat::R64G64_SINT,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R64G64_SFLOAT => ReflectFormat::R64G64_SFLOAT,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R64G64B64_UINT => ReflectFormat::R64G64B64_UINT,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R64G64B64_SINT => ReflectFormat::R64G64B64_SINT,
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R64G64B64_SFLOAT => {
+            ReflectFormat::R64G64B64_SFLOAT
+        }
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R64G64B64A64_UINT => {
+            ReflectFormat::R64G64B64A64_UINT
+        }
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R64G64B64A64_SINT => {
+            ReflectFormat::R64G64B64A64_SINT
+        }
+        ffi::SpvReflectFormat_SPV_REFLECT_FORMAT_R64G64B64A64_SFLOAT => {
+            ReflectFormat::R64G64B64A64_SFLOAT
+        }
+        _ => ReflectFormat::Unknown(ffi_type),
     }
 }
 
 pub(crate) fn ffi_to_storage_class(ffi_type: ffi::SpvStorageClass) -> ReflectStorageClass {
-    if ffi_type as u32 == std::u32::MAX {
+    if ffi_type == u32::MAX {
         return ReflectStorageClass::Undefined;
     }
 
@@ -411,28 +628,64 @@ pub(crate) fn ffi_to_storage_class(ffi_type: ffi::SpvStorageClass) -> ReflectSto
         ffi::SpvStorageClass__SpvStorageClassAtomicCounter => ReflectStorageClass::AtomicCounter,
         ffi::SpvStorageClass__SpvStorageClassImage => ReflectStorageClass::Image,
         ffi::SpvStorageClass__SpvStorageClassStorageBuffer => ReflectStorageClass::StorageBuffer,
-        ffi::SpvStorageClass__SpvStorageClassMax => ReflectStorageClass::Undefined,
-        _ => {
-            println!("value is {}", ffi_type);
-            unimplemented!()
+        ffi::SpvStorageClass__SpvStorageClassTileImageEXT => ReflectStorageClass::TileImageEXT,
+        ffi::SpvStorageClass__SpvStorageClassCallableDataKHR => {
+            ReflectStorageClass::CallableDataKHR
+        }
+        ffi::SpvStorageClass__SpvStorageClassIncomingCallableDataKHR => {
+            ReflectStorageClass::IncomingCallableDataKHR
+        }
+        ffi::SpvStorageClass__SpvStorageClassRayPayloadKHR => ReflectStorageClass::RayPayloadKHR,
+        ffi::SpvStorageClass__SpvStorageClassHitAttributeKHR => {
+            ReflectStorageClass::HitAttributeKHR
+        }
+        ffi::SpvStorageClass__SpvStorageClassIncomingRayPayloadKHR => {
+            ReflectStorageClass::IncomingRayPayloadKHR
+        }
+        ffi::SpvStorageClass__SpvStorageClassShaderRecordBufferKHR => {
+            ReflectStorageClass::ShaderRecordBufferKHR
+        }
+        ffi::SpvStorageClass__SpvStorageClassPhysicalStorageBuffer => {
+            ReflectStorageClass::PhysicalStorageBuffer
+        }
+        ffi::SpvStorageClass__SpvStorageClassHitObjectAttributeNV => {
+            ReflectStorageClass::HitObjectAttributeNV
+        }
+        ffi::SpvStorageClass__SpvStorageClassTaskPayloadWorkgroupEXT => {
+            ReflectStorageClass::TaskPayloadWorkgroupEXT
+        }
+        ffi::SpvStorageClass__SpvStorageClassCodeSectionINTEL => {
+            ReflectStorageClass::CodeSectionINTEL
+        }
+        ffi::SpvStorageClass__SpvStorageClassDeviceOnlyINTEL => {
+            ReflectStorageClass::DeviceOnlyINTEL
         }
+        ffi::SpvStorageClass__SpvStorageClassHostOnlyINTEL => ReflectStorageClass::HostOnlyINTEL,
+        ffi::SpvStorageClass__SpvStorageClassMax => ReflectStorageClass::Undefined,
+        _ => ReflectStorageClass::Unknown(ffi_type),
     }
 }
 
 pub(crate) fn ffi_to_shader_stage_flags(
     ffi_type: ffi::SpvReflectShaderStageFlagBits,
 ) -> ReflectShaderStageFlags {
-    ReflectShaderStageFlags::from_bits(ffi_type as u32).unwrap()
+    ReflectShaderStageFlags::from_bits_truncate(ffi_type)
 }
 
 pub(crate) fn ffi_to_type_flags(ffi_type: ffi::SpvReflectTypeFlagBits) -> ReflectTypeFlags {
-    ReflectTypeFlags::from_bits(ffi_type as u32).unwrap()
+    ReflectTypeFlags::from_bits_truncate(ffi_type)
 }
 
 pub(crate) fn ffi_to_decoration_flags(
     ffi_type: ffi::SpvReflectDecorationFlags,
 ) -> ReflectDecorationFlags {
-    ReflectDecorationFlags::from_bits(ffi_type).unwrap()
+    ReflectDecorationFlags::from_bits_truncate(ffi_type)
+}
+
+pub(crate) fn ffi_to_variable_flags(
+    ffi_type: ffi::SpvReflectVariableFlags,
+) -> ReflectVariableFlags {
+    ReflectVariableFlags::from_bits_truncate(ffi_type)
 }
 
 pub(crate) fn ffi_to_numeric_traits(
@@ -476,10 +729,8 @@ pub(crate) fn ffi_to_block_variable(
 ) -> ReflectBlockVariable {
     let ffi_members =
         unsafe { ffi_slice_from_raw_parts(ffi_type.members, ffi_type.member_count as usize) };
-    let members: Vec<ReflectBlockVariable> = ffi_members
-        .iter()
-        .map(|member| ffi_to_block_variable(member))
-        .collect();
+    let members: Vec<ReflectBlockVariable> =
+        ffi_members.iter().map(ffi_to_block_variable).collect();
     ReflectBlockVariable {
         spirv_id: ffi_type.spirv_id,
         name: super::ffi_to_string(ffi_type.name),
@@ -490,6 +741,7 @@ pub(crate) fn ffi_to_block_variable(
         decoration_flags: ffi_to_decoration_flags(ffi_type.decoration_flags),
         numeric: ffi_to_numeric_traits(ffi_type.numeric),
         array: ffi_to_array_traits(ffi_type.array),
+        flags: ffi_to_variable_flags(ffi_type.flags),
         members,
         type_description: if ffi_type.type_description.is_null() {
             None
@@ -501,34 +753,207 @@ pub(crate) fn ffi_to_block_variable(
     }
 }
 
+// Shares the messages of `ReflectErrorKind`, so the two can't drift apart.
 pub fn result_to_string(result: ffi::SpvReflectResult) -> &'static str {
     match result {
         ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS => "Success",
-        ffi::SpvReflectResult_SPV_REFLECT_RESULT_NOT_READY => "Result Not Ready",
-        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_PARSE_FAILED => "Parse Failed",
-        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_ALLOC_FAILED => "Allocation Failed",
-        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_RANGE_EXCEEDED => "Range Exceeded",
-        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_NULL_POINTER => "Null Pointer",
-        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_INTERNAL_ERROR => "Internal Error",
-        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_COUNT_MISMATCH => "Count Mismatch",
-        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_ELEMENT_NOT_FOUND => "Element Not Found",
+        _ => result_to_error(result).message(),
+    }
+}
+
+pub fn result_to_error(result: ffi::SpvReflectResult) -> ReflectError {
+    ReflectError::new(match result {
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_NOT_READY => ReflectErrorKind::NotReady,
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_PARSE_FAILED => {
+            ReflectErrorKind::ParseFailed
+        }
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_ALLOC_FAILED => {
+            ReflectErrorKind::AllocFailed
+        }
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_RANGE_EXCEEDED => {
+            ReflectErrorKind::RangeExceeded
+        }
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_NULL_POINTER => {
+            ReflectErrorKind::NullPointer
+        }
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_INTERNAL_ERROR => {
+            ReflectErrorKind::InternalError
+        }
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_COUNT_MISMATCH => {
+            ReflectErrorKind::CountMismatch
+        }
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_ELEMENT_NOT_FOUND => {
+            ReflectErrorKind::ElementNotFound
+        }
         ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_SPIRV_INVALID_CODE_SIZE => {
-            "Invalid Code Size"
+            ReflectErrorKind::SpirvInvalidCodeSize
         }
         ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_SPIRV_INVALID_MAGIC_NUMBER => {
-            "Invalid Magic Number"
+            ReflectErrorKind::SpirvInvalidMagicNumber
+        }
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_SPIRV_UNEXPECTED_EOF => {
+            ReflectErrorKind::SpirvUnexpectedEof
         }
-        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_SPIRV_UNEXPECTED_EOF => "Unexpected EoF",
         ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_SPIRV_INVALID_ID_REFERENCE => {
-            "Invalid ID Reference"
+            ReflectErrorKind::SpirvInvalidIdReference
         }
         ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_SPIRV_SET_NUMBER_OVERFLOW => {
-            "Set Number Overflow"
+            ReflectErrorKind::SpirvSetNumberOverflow
         }
         ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_SPIRV_INVALID_STORAGE_CLASS => {
-            "Invalid Storage Class"
+            ReflectErrorKind::SpirvInvalidStorageClass
+        }
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_SPIRV_RECURSION => {
+            ReflectErrorKind::SpirvRecursion
+        }
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_SPIRV_INVALID_INSTRUCTION => {
+            ReflectErrorKind::SpirvInvalidInstruction
+        }
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_SPIRV_UNEXPECTED_BLOCK_DATA => {
+            ReflectErrorKind::SpirvUnexpectedBlockData
+        }
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_SPIRV_INVALID_BLOCK_MEMBER_REFERENCE => {
+            ReflectErrorKind::SpirvInvalidBlockMemberReference
+        }
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_SPIRV_INVALID_ENTRY_POINT => {
+            ReflectErrorKind::SpirvInvalidEntryPoint
+        }
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_SPIRV_INVALID_EXECUTION_MODE => {
+            ReflectErrorKind::SpirvInvalidExecutionMode
+        }
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_SPIRV_MAX_RECURSIVE_EXCEEDED => {
+            ReflectErrorKind::SpirvMaxRecursiveExceeded
+        }
+        _ => ReflectErrorKind::Unknown(result),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw_values(prefix: &str) -> Vec<u32> {
+        include_str!("../gen/bindings.rs")
+            .split("pub const ")
+            .skip(1)
+            // The `*Max` constants only force the enum width and are not real values.
+            .filter(|constant| constant.starts_with(prefix) && !constant.contains("Max:"))
+            .map(|constant| {
+                let value = &constant[constant.find('=').unwrap() + 1..constant.find(';').unwrap()];
+                value.trim().parse().unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn convert_every_raw_value() {
+        const UNDECLARED: u32 = 0x7fff_fffe;
+
+        let values = raw_values("SpvReflectDescriptorType_");
+        assert!(!values.is_empty());
+        for value in values {
+            assert_ne!(
+                ffi_to_descriptor_type(value),
+                ReflectDescriptorType::Unknown(value)
+            );
+        }
+        assert_eq!(
+            ffi_to_descriptor_type(UNDECLARED),
+            ReflectDescriptorType::Unknown(UNDECLARED)
+        );
+
+        let values = raw_values("SpvReflectResourceType_");
+        assert!(!values.is_empty());
+        for value in values {
+            assert_ne!(
+                ffi_to_resource_type(value),
+                ReflectResourceType::Unknown(value)
+            );
+        }
+        assert_eq!(
+            ffi_to_resource_type(UNDECLARED),
+            ReflectResourceType::Unknown(UNDECLARED)
+        );
+
+        let values = raw_values("SpvDim__");
+        assert!(!values.is_empty());
+        for value in values {
+            assert_ne!(ffi_to_dimension(value), ReflectDimension::Unknown(value));
+        }
+        assert_eq!(
+            ffi_to_dimension(UNDECLARED),
+            ReflectDimension::Unknown(UNDECLARED)
+        );
+
+        let values = raw_values("SpvImageFormat__");
+        assert!(!values.is_empty());
+        for value in values {
+            assert_ne!(
+                ffi_to_image_format(value),
+                ReflectImageFormat::Unknown(value)
+            );
+        }
+        assert_eq!(
+            ffi_to_image_format(UNDECLARED),
+            ReflectImageFormat::Unknown(UNDECLARED)
+        );
+
+        let values = raw_values("SpvReflectFormat_");
+        assert!(!values.is_empty());
+        for value in values {
+            assert_ne!(ffi_to_format(value), ReflectFormat::Unknown(value));
+        }
+        assert_eq!(
+            ffi_to_format(UNDECLARED),
+            ReflectFormat::Unknown(UNDECLARED)
+        );
+
+        let values = raw_values("SpvStorageClass__");
+        assert!(!values.is_empty());
+        for value in values {
+            assert_ne!(
+                ffi_to_storage_class(value),
+                ReflectStorageClass::Unknown(value)
+            );
+        }
+        assert_eq!(
+            ffi_to_storage_class(UNDECLARED),
+            ReflectStorageClass::Unknown(UNDECLARED)
+        );
+
+        let values = raw_values("SpvReflectUserType_");
+        assert!(!values.is_empty());
+        for value in values {
+            assert_ne!(ffi_to_user_type(value), ReflectUserType::Unknown(value));
+        }
+
+        let values = raw_values("SpvReflectGenerator_");
+        assert!(!values.is_empty());
+        for value in values {
+            assert_ne!(
+                ffi_to_generator(value),
+                ReflectGenerator::Unknown,
+                "{}",
+                value
+            );
+        }
+        for value in raw_values("SpvReflectShaderStageFlagBits_") {
+            assert_eq!(ffi_to_shader_stage_flags(value).bits(), value);
         }
-        ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_SPIRV_RECURSION => "Spirv Recursion",
-        _ => unimplemented!(),
+        for value in raw_values("SpvReflectTypeFlagBits_") {
+            ffi_to_type_flags(value);
+        }
+        for value in raw_values("SpvReflectDecorationFlagBits_") {
+            ffi_to_decoration_flags(value);
+        }
+
+        for value in raw_values("SpvReflectResult_") {
+            if value != ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS {
+                let error = result_to_error(value);
+                assert_ne!(error.kind(), ReflectErrorKind::Unknown(value));
+                assert_eq!(result_to_string(value), error.message());
+            }
+        }
+        assert_eq!(result_to_string(UNDECLARED), "Unknown Error");
     }
 }
@@ -0,0 +1,142 @@
+use std::fmt;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReflectErrorContext {
+    pub entry_point: Option<String>,
+    pub set: Option<u32>,
+    pub binding: Option<u32>,
+    pub word_offset: Option<u32>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ReflectErrorKind {
+    NotReady,
+    ParseFailed,
+    AllocFailed,
+    RangeExceeded,
+    NullPointer,
+    InternalError,
+    CountMismatch,
+    ElementNotFound,
+    SpirvInvalidCodeSize,
+    SpirvInvalidMagicNumber,
+    SpirvUnexpectedEof,
+    SpirvInvalidIdReference,
+    SpirvSetNumberOverflow,
+    SpirvInvalidStorageClass,
+    SpirvRecursion,
+    SpirvInvalidInstruction,
+    SpirvUnexpectedBlockData,
+    SpirvInvalidBlockMemberReference,
+    SpirvInvalidEntryPoint,
+    SpirvInvalidExecutionMode,
+    SpirvMaxRecursiveExceeded,
+    Unknown(u32),
+}
+
+impl ReflectErrorKind {
+    pub fn message(self) -> &'static str {
+        match self {
+            ReflectErrorKind::NotReady => "Result Not Ready",
+            ReflectErrorKind::ParseFailed => "Parse Failed",
+            ReflectErrorKind::AllocFailed => "Allocation Failed",
+            ReflectErrorKind::RangeExceeded => "Range Exceeded",
+            ReflectErrorKind::NullPointer => "Null Pointer",
+            ReflectErrorKind::InternalError => "Internal Error",
+            ReflectErrorKind::CountMismatch => "Count Mismatch",
+            ReflectErrorKind::ElementNotFound => "Element Not Found",
+            ReflectErrorKind::SpirvInvalidCodeSize => "Invalid Code Size",
+            ReflectErrorKind::SpirvInvalidMagicNumber => "Invalid Magic Number",
+            ReflectErrorKind::SpirvUnexpectedEof => "Unexpected EoF",
+            ReflectErrorKind::SpirvInvalidIdReference => "Invalid ID Reference",
+            ReflectErrorKind::SpirvSetNumberOverflow => "Set Number Overflow",
+            ReflectErrorKind::SpirvInvalidStorageClass => "Invalid Storage Class",
+            ReflectErrorKind::SpirvRecursion => "Spirv Recursion",
+            ReflectErrorKind::SpirvInvalidInstruction => "Invalid Instruction",
+            ReflectErrorKind::SpirvUnexpectedBlockData => "Unexpected Block Data",
+            ReflectErrorKind::SpirvInvalidBlockMemberReference => "Invalid Block Member Reference",
+            ReflectErrorKind::SpirvInvalidEntryPoint => "Invalid Entry Point",
+            ReflectErrorKind::SpirvInvalidExecutionMode => "Invalid Execution Mode",
+            ReflectErrorKind::SpirvMaxRecursiveExceeded => "Max Recursion Exceeded",
+            ReflectErrorKind::Unknown(_) => "Unknown Error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReflectError {
+    kind: ReflectErrorKind,
+    context: ReflectErrorContext,
+}
+
+impl ReflectError {
+    pub fn new(kind: ReflectErrorKind) -> Self {
+        ReflectError {
+            kind,
+            context: ReflectErrorContext::default(),
+        }
+    }
+
+    pub fn kind(&self) -> ReflectErrorKind {
+        self.kind
+    }
+
+    pub fn context(&self) -> &ReflectErrorContext {
+        &self.context
+    }
+
+    pub fn with_entry_point(mut self, entry_point: Option<&str>) -> Self {
+        self.context.entry_point = entry_point.map(str::to_owned);
+        self
+    }
+
+    pub fn with_binding(mut self, set: u32, binding: u32) -> Self {
+        self.context.set = Some(set);
+        self.context.binding = Some(binding);
+        self
+    }
+
+    pub fn with_set(mut self, set: u32) -> Self {
+        self.context.set = Some(set);
+        self
+    }
+
+    pub fn with_word_offset(mut self, word_offset: u32) -> Self {
+        self.context.word_offset = Some(word_offset);
+        self
+    }
+
+    pub fn message(&self) -> &'static str {
+        self.kind.message()
+    }
+}
+
+impl From<ReflectErrorKind> for ReflectError {
+    fn from(kind: ReflectErrorKind) -> Self {
+        ReflectError::new(kind)
+    }
+}
+
+impl fmt::Display for ReflectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ReflectErrorKind::Unknown(result) => write!(f, "{} ({})", self.message(), result)?,
+            _ => write!(f, "{}", self.message())?,
+        }
+        if let Some(ref entry_point) = self.context.entry_point {
+            write!(f, ", entry point: {}", entry_point)?;
+        }
+        if let Some(set) = self.context.set {
+            write!(f, ", set: {}", set)?;
+        }
+        if let Some(binding) = self.context.binding {
+            write!(f, ", binding: {}", binding)?;
+        }
+        if let Some(word_offset) = self.context.word_offset {
+            write!(f, ", word offset: {}", word_offset)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ReflectError {}
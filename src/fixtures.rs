@@ -0,0 +1,76 @@
+// Builders for the hand-written reflection data used by the unit tests. Tests
+// tweak the returned structs in place for anything beyond these defaults.
+
+use crate::types::*;
+
+pub(crate) fn numeric(width: u32, signedness: u32, component_count: u32) -> ReflectNumericTraits {
+    ReflectNumericTraits {
+        scalar: ReflectNumericTraitsScalar { width, signedness },
+        vector: ReflectNumericTraitsVector { component_count },
+        ..Default::default()
+    }
+}
+
+pub(crate) fn float(component_count: u32) -> ReflectNumericTraits {
+    numeric(32, 0, component_count)
+}
+
+pub(crate) fn block_member(
+    name: &str,
+    offset: u32,
+    type_flags: ReflectTypeFlags,
+    numeric: ReflectNumericTraits,
+) -> ReflectBlockVariable {
+    ReflectBlockVariable {
+        name: name.to_owned(),
+        offset,
+        numeric,
+        type_description: Some(ReflectTypeDescription {
+            type_flags,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+pub(crate) fn interface_variable(
+    name: &str,
+    location: u32,
+    format: ReflectFormat,
+    numeric: ReflectNumericTraits,
+) -> ReflectInterfaceVariable {
+    ReflectInterfaceVariable {
+        spirv_id: 0,
+        name: name.to_owned(),
+        location,
+        component: 0,
+        storage_class: ReflectStorageClass::Input,
+        semantic: String::new(),
+        decoration_flags: ReflectDecorationFlags::NONE,
+        built_in: ReflectBuiltIn::default(),
+        numeric,
+        array: ReflectArrayTraits::default(),
+        members: Vec::new(),
+        format,
+        type_description: Some(ReflectTypeDescription {
+            type_flags: ReflectTypeFlags::FLOAT,
+            ..Default::default()
+        }),
+        word_offset: 0,
+    }
+}
+
+pub(crate) fn descriptor_binding(
+    name: &str,
+    set: u32,
+    binding: u32,
+    descriptor_type: ReflectDescriptorType,
+) -> ReflectDescriptorBinding {
+    ReflectDescriptorBinding {
+        name: name.to_owned(),
+        set,
+        binding,
+        descriptor_type,
+        ..Default::default()
+    }
+}
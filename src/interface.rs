@@ -0,0 +1,565 @@
+use crate::error::{ReflectError, ReflectErrorKind};
+use crate::types::{
+    ReflectDecorationFlags, ReflectFormat, ReflectInterfaceVariable, ReflectNumericTraits,
+    ReflectShaderStageFlags,
+};
+use crate::ReflectModule;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum InterfaceIssue {
+    MissingOutput {
+        location: u32,
+        input: String,
+    },
+    UnusedOutput {
+        location: u32,
+        output: String,
+    },
+    FormatMismatch {
+        location: u32,
+        output: String,
+        input: String,
+        output_format: ReflectFormat,
+        input_format: ReflectFormat,
+    },
+    NumericMismatch {
+        location: u32,
+        output: String,
+        input: String,
+        output_numeric: ReflectNumericTraits,
+        input_numeric: ReflectNumericTraits,
+    },
+    ArrayMismatch {
+        location: u32,
+        output: String,
+        input: String,
+        output_dims: Vec<u32>,
+        input_dims: Vec<u32>,
+    },
+    InterpolationMismatch {
+        location: u32,
+        output: String,
+        input: String,
+        output_flags: ReflectDecorationFlags,
+        input_flags: ReflectDecorationFlags,
+    },
+    DuplicateOutput {
+        location: u32,
+        first: String,
+        second: String,
+    },
+    DuplicateInput {
+        location: u32,
+        first: String,
+        second: String,
+    },
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InterfaceReport {
+    pub issues: Vec<InterfaceIssue>,
+}
+
+impl InterfaceReport {
+    // Outputs the next stage doesn't read are allowed; everything else fails pipeline creation
+    // or leaves inputs undefined.
+    pub fn is_compatible(&self) -> bool {
+        self.issues
+            .iter()
+            .all(|issue| matches!(issue, InterfaceIssue::UnusedOutput { .. }))
+    }
+}
+
+const INTERPOLATION_FLAGS: ReflectDecorationFlags = ReflectDecorationFlags::from_bits_truncate(
+    ReflectDecorationFlags::FLAT.bits() | ReflectDecorationFlags::NO_PERSPECTIVE.bits(),
+);
+
+struct Slot<'a> {
+    name: String,
+    variable: &'a ReflectInterfaceVariable,
+    dims: Vec<u32>,
+    end: u32,
+}
+
+// Inputs of tessellation and geometry shaders and outputs of tessellation control and mesh
+// shaders have an extra outer array with one element per vertex or primitive.
+fn is_arrayed(stage: ReflectShaderStageFlags, is_output: bool) -> bool {
+    if is_output {
+        stage.intersects(
+            ReflectShaderStageFlags::TESSELLATION_CONTROL | ReflectShaderStageFlags::MESH_EXT,
+        )
+    } else {
+        stage.intersects(
+            ReflectShaderStageFlags::TESSELLATION_CONTROL
+                | ReflectShaderStageFlags::TESSELLATION_EVALUATION
+                | ReflectShaderStageFlags::GEOMETRY,
+        )
+    }
+}
+
+// Matrices take one location per column and arrays one per element. 64-bit vectors with
+// more than two components take two locations each.
+fn location_count(variable: &ReflectInterfaceVariable, dims: &[u32]) -> u32 {
+    let element_count = if variable.members.is_empty() {
+        let numeric = &variable.numeric;
+        let (columns, components) = if numeric.matrix.column_count > 0 {
+            (numeric.matrix.column_count, numeric.matrix.row_count)
+        } else {
+            (1, numeric.vector.component_count.max(1))
+        };
+        if numeric.scalar.width > 32 && components > 2 {
+            columns.saturating_mul(2)
+        } else {
+            columns
+        }
+    } else {
+        variable.members.iter().fold(0, |count: u32, member| {
+            count.saturating_add(location_count(member, &member.array.dims))
+        })
+    };
+    dims.iter()
+        .fold(element_count, |count, &dim| count.saturating_mul(dim))
+}
+
+// Block members without a Location decoration take consecutive locations after the
+// previous member, starting at the location of the block.
+fn collect_slots<'a>(
+    variables: &'a [ReflectInterfaceVariable],
+    parent: Option<(&str, u32)>,
+    arrayed: bool,
+    slots: &mut BTreeMap<(u32, u32), Slot<'a>>,
+    duplicates: &mut Vec<(u32, String, String)>,
+) {
+    let mut next_location = parent.map(|(_, location)| location);
+    for variable in variables {
+        let location = match next_location {
+            Some(location) if variable.location == u32::MAX => location,
+            _ => variable.location,
+        };
+        let arrayed = arrayed
+            && !variable
+                .decoration_flags
+                .contains(ReflectDecorationFlags::PATCH);
+        let dims: Vec<u32> = if arrayed {
+            variable.array.dims.iter().skip(1).copied().collect()
+        } else {
+            variable.array.dims.clone()
+        };
+        if parent.is_some() {
+            next_location = Some(location.saturating_add(location_count(variable, &dims)));
+        }
+        if variable
+            .decoration_flags
+            .contains(ReflectDecorationFlags::BUILT_IN)
+        {
+            continue;
+        }
+        let name = match parent {
+            Some((parent, _)) => format!("{}.{}", parent, variable.name),
+            None => variable.name.clone(),
+        };
+        if !variable.members.is_empty() {
+            collect_slots(
+                &variable.members,
+                Some((&name, location)),
+                arrayed,
+                slots,
+                duplicates,
+            );
+            continue;
+        }
+        // Packed varyings share a location and are told apart by their first component.
+        let component = if variable.component == u32::MAX {
+            0
+        } else {
+            variable.component
+        };
+        // Matrices and arrays also occupy the locations after their first one.
+        let end = location.saturating_add(location_count(variable, &dims).max(1));
+        let overlap = slots.iter().find(|(&(start, slot_component), slot)| {
+            slot_component == component && start < end && location < slot.end
+        });
+        match overlap {
+            Some((&(start, _), slot)) => {
+                duplicates.push((start.max(location), slot.name.clone(), name));
+            }
+            None => {
+                slots.insert(
+                    (location, component),
+                    Slot {
+                        name,
+                        variable,
+                        dims,
+                        end,
+                    },
+                );
+            }
+        }
+    }
+}
+
+fn numeric_matches(output: &ReflectNumericTraits, input: &ReflectNumericTraits) -> bool {
+    output.scalar == input.scalar
+        && output.vector.component_count == input.vector.component_count
+        && output.matrix.column_count == input.matrix.column_count
+        && output.matrix.row_count == input.matrix.row_count
+}
+
+pub fn check_interface_variables(
+    outputs: &[ReflectInterfaceVariable],
+    output_stage: ReflectShaderStageFlags,
+    inputs: &[ReflectInterfaceVariable],
+    input_stage: ReflectShaderStageFlags,
+) -> InterfaceReport {
+    let mut report = InterfaceReport::default();
+    let mut output_slots = BTreeMap::new();
+    let mut duplicates = Vec::new();
+    collect_slots(
+        outputs,
+        None,
+        is_arrayed(output_stage, true),
+        &mut output_slots,
+        &mut duplicates,
+    );
+    report
+        .issues
+        .extend(duplicates.drain(..).map(|(location, first, second)| {
+            InterfaceIssue::DuplicateOutput {
+                location,
+                first,
+                second,
+            }
+        }));
+    let mut input_slots = BTreeMap::new();
+    collect_slots(
+        inputs,
+        None,
+        is_arrayed(input_stage, false),
+        &mut input_slots,
+        &mut duplicates,
+    );
+    report
+        .issues
+        .extend(duplicates.into_iter().map(|(location, first, second)| {
+            InterfaceIssue::DuplicateInput {
+                location,
+                first,
+                second,
+            }
+        }));
+
+    for (&(location, component), input) in &input_slots {
+        let output = match output_slots.get(&(location, component)) {
+            Some(output) => output,
+            None => {
+                report.issues.push(InterfaceIssue::MissingOutput {
+                    location,
+                    input: input.name.clone(),
+                });
+                continue;
+            }
+        };
+        let (output_variable, input_variable) = (output.variable, input.variable);
+        if output_variable.format != input_variable.format {
+            report.issues.push(InterfaceIssue::FormatMismatch {
+                location,
+                output: output.name.clone(),
+                input: input.name.clone(),
+                output_format: output_variable.format,
+                input_format: input_variable.format,
+            });
+        } else if !numeric_matches(&output_variable.numeric, &input_variable.numeric) {
+            report.issues.push(InterfaceIssue::NumericMismatch {
+                location,
+                output: output.name.clone(),
+                input: input.name.clone(),
+                output_numeric: output_variable.numeric,
+                input_numeric: input_variable.numeric,
+            });
+        }
+        if output.dims != input.dims {
+            report.issues.push(InterfaceIssue::ArrayMismatch {
+                location,
+                output: output.name.clone(),
+                input: input.name.clone(),
+                output_dims: output.dims.clone(),
+                input_dims: input.dims.clone(),
+            });
+        }
+        let output_flags = output_variable.decoration_flags & INTERPOLATION_FLAGS;
+        let input_flags = input_variable.decoration_flags & INTERPOLATION_FLAGS;
+        if output_flags != input_flags {
+            report.issues.push(InterfaceIssue::InterpolationMismatch {
+                location,
+                output: output.name.clone(),
+                input: input.name.clone(),
+                output_flags,
+                input_flags,
+            });
+        }
+    }
+    for (&(location, component), output) in &output_slots {
+        if !input_slots.contains_key(&(location, component)) {
+            report.issues.push(InterfaceIssue::UnusedOutput {
+                location,
+                output: output.name.clone(),
+            });
+        }
+    }
+    report
+}
+
+fn entry_point_stage(
+    module: &impl ReflectModule,
+    entry_point: Option<&str>,
+) -> Result<ReflectShaderStageFlags, ReflectError> {
+    let name = match entry_point {
+        Some(name) => name,
+        None => return Ok(module.get_shader_stage()),
+    };
+    module
+        .enumerate_entry_points()?
+        .into_iter()
+        .find(|entry| entry.name == name)
+        .map(|entry| entry.shader_stage)
+        .ok_or_else(|| {
+            ReflectError::new(ReflectErrorKind::ElementNotFound).with_entry_point(Some(name))
+        })
+}
+
+// Compares the outputs of `producer` with the inputs of the following stage, `consumer`.
+pub fn check_stage_interface(
+    producer: &impl ReflectModule,
+    producer_entry_point: Option<&str>,
+    consumer: &impl ReflectModule,
+    consumer_entry_point: Option<&str>,
+) -> Result<InterfaceReport, ReflectError> {
+    let output_stage = entry_point_stage(producer, producer_entry_point)?;
+    let input_stage = entry_point_stage(consumer, consumer_entry_point)?;
+    let outputs = producer.enumerate_output_variables(producer_entry_point)?;
+    let inputs = consumer.enumerate_input_variables(consumer_entry_point)?;
+    Ok(check_interface_variables(
+        &outputs,
+        output_stage,
+        &inputs,
+        input_stage,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{float, interface_variable};
+    use crate::types::*;
+
+    #[test]
+    fn vertex_to_fragment() {
+        let mut color =
+            interface_variable("color", 1, ReflectFormat::R32G32B32A32_SFLOAT, float(4));
+        color.decoration_flags = ReflectDecorationFlags::FLAT;
+        let outputs = vec![
+            interface_variable("uv", 0, ReflectFormat::R32G32_SFLOAT, float(2)),
+            color,
+            interface_variable("normal", 2, ReflectFormat::R32G32B32_SFLOAT, float(3)),
+        ];
+        let inputs = vec![
+            interface_variable("uv", 0, ReflectFormat::R32G32B32_SFLOAT, float(3)),
+            interface_variable("color", 1, ReflectFormat::R32G32B32A32_SFLOAT, float(4)),
+            interface_variable("tangent", 3, ReflectFormat::R32G32B32_SFLOAT, float(3)),
+        ];
+        let report = check_interface_variables(
+            &outputs,
+            ReflectShaderStageFlags::VERTEX,
+            &inputs,
+            ReflectShaderStageFlags::FRAGMENT,
+        );
+        assert_eq!(
+            report.issues,
+            vec![
+                InterfaceIssue::FormatMismatch {
+                    location: 0,
+                    output: "uv".to_owned(),
+                    input: "uv".to_owned(),
+                    output_format: ReflectFormat::R32G32_SFLOAT,
+                    input_format: ReflectFormat::R32G32B32_SFLOAT,
+                },
+                InterfaceIssue::InterpolationMismatch {
+                    location: 1,
+                    output: "color".to_owned(),
+                    input: "color".to_owned(),
+                    output_flags: ReflectDecorationFlags::FLAT,
+                    input_flags: ReflectDecorationFlags::NONE,
+                },
+                InterfaceIssue::MissingOutput {
+                    location: 3,
+                    input: "tangent".to_owned(),
+                },
+                InterfaceIssue::UnusedOutput {
+                    location: 2,
+                    output: "normal".to_owned(),
+                },
+            ]
+        );
+        assert!(!report.is_compatible());
+    }
+
+    #[test]
+    fn arrayed_stages() {
+        let mut output = interface_variable("uv", 0, ReflectFormat::R32G32_SFLOAT, float(2));
+        output.array.dims = vec![64];
+        let input = interface_variable("uv", 0, ReflectFormat::R32G32_SFLOAT, float(2));
+        let report = check_interface_variables(
+            &[output],
+            ReflectShaderStageFlags::MESH_EXT,
+            std::slice::from_ref(&input),
+            ReflectShaderStageFlags::FRAGMENT,
+        );
+        assert!(report.issues.is_empty());
+
+        let mut arrayed_input = input.clone();
+        arrayed_input.array.dims = vec![3];
+        let report = check_interface_variables(
+            std::slice::from_ref(&input),
+            ReflectShaderStageFlags::VERTEX,
+            std::slice::from_ref(&arrayed_input),
+            ReflectShaderStageFlags::GEOMETRY,
+        );
+        assert!(report.is_compatible());
+
+        let report = check_interface_variables(
+            &[input],
+            ReflectShaderStageFlags::VERTEX,
+            &[arrayed_input],
+            ReflectShaderStageFlags::FRAGMENT,
+        );
+        assert_eq!(
+            report.issues,
+            vec![InterfaceIssue::ArrayMismatch {
+                location: 0,
+                output: "uv".to_owned(),
+                input: "uv".to_owned(),
+                output_dims: vec![],
+                input_dims: vec![3],
+            }]
+        );
+    }
+
+    #[test]
+    fn packed_components() {
+        let uv = interface_variable("uv", 1, ReflectFormat::R32G32_SFLOAT, float(2));
+        let mut fog = interface_variable("fog", 1, ReflectFormat::R32_SFLOAT, float(1));
+        fog.component = 2;
+        let report = check_interface_variables(
+            &[uv.clone(), fog.clone()],
+            ReflectShaderStageFlags::VERTEX,
+            &[fog, uv],
+            ReflectShaderStageFlags::FRAGMENT,
+        );
+        assert!(report.issues.is_empty());
+    }
+
+    #[test]
+    fn block_members() {
+        let mut transform = interface_variable(
+            "transform",
+            u32::MAX,
+            ReflectFormat::R32G32_SFLOAT,
+            float(2),
+        );
+        transform.numeric.matrix = ReflectNumericTraitsMatrix {
+            column_count: 2,
+            row_count: 2,
+            stride: 8,
+        };
+        let mut fog = interface_variable("fog", 7, ReflectFormat::R32_SFLOAT, float(1));
+        fog.component = u32::MAX;
+        let mut block = interface_variable("Block", 3, ReflectFormat::Undefined, float(0));
+        block.members = vec![
+            interface_variable("uv", u32::MAX, ReflectFormat::R32G32_SFLOAT, float(2)),
+            transform.clone(),
+            interface_variable("tint", u32::MAX, ReflectFormat::R32_SFLOAT, float(1)),
+            fog,
+        ];
+        transform.location = 4;
+        let inputs = vec![
+            interface_variable("uv", 3, ReflectFormat::R32G32_SFLOAT, float(2)),
+            transform,
+            interface_variable("tint", 6, ReflectFormat::R32_SFLOAT, float(1)),
+            interface_variable("fog", 7, ReflectFormat::R32_SFLOAT, float(1)),
+        ];
+        let report = check_interface_variables(
+            std::slice::from_ref(&block),
+            ReflectShaderStageFlags::VERTEX,
+            &inputs,
+            ReflectShaderStageFlags::FRAGMENT,
+        );
+        assert!(report.issues.is_empty());
+
+        let outputs = vec![
+            block,
+            interface_variable("normal", 4, ReflectFormat::R32G32_SFLOAT, float(2)),
+        ];
+        let report = check_interface_variables(
+            &outputs,
+            ReflectShaderStageFlags::VERTEX,
+            &inputs[..1],
+            ReflectShaderStageFlags::FRAGMENT,
+        );
+        assert_eq!(
+            report.issues[0],
+            InterfaceIssue::DuplicateOutput {
+                location: 4,
+                first: "Block.transform".to_owned(),
+                second: "normal".to_owned(),
+            }
+        );
+        assert!(!report.is_compatible());
+    }
+
+    #[test]
+    fn overlapping_locations() {
+        let mut transform =
+            interface_variable("transform", 0, ReflectFormat::R32G32B32_SFLOAT, float(3));
+        transform.numeric.matrix = ReflectNumericTraitsMatrix {
+            column_count: 3,
+            row_count: 3,
+            stride: 16,
+        };
+        let mut weights = interface_variable("weights", 3, ReflectFormat::R32_SFLOAT, float(1));
+        weights.array.dims = vec![4];
+        let outputs = vec![
+            transform,
+            weights,
+            interface_variable("uv", 2, ReflectFormat::R32G32_SFLOAT, float(2)),
+            interface_variable("fog", 5, ReflectFormat::R32_SFLOAT, float(1)),
+            interface_variable("color", 7, ReflectFormat::R32G32B32A32_SFLOAT, float(4)),
+        ];
+        let report = check_interface_variables(
+            &outputs,
+            ReflectShaderStageFlags::VERTEX,
+            &[],
+            ReflectShaderStageFlags::FRAGMENT,
+        );
+        let duplicates: Vec<&InterfaceIssue> = report
+            .issues
+            .iter()
+            .filter(|issue| !matches!(issue, InterfaceIssue::UnusedOutput { .. }))
+            .collect();
+        assert_eq!(
+            duplicates,
+            vec![
+                &InterfaceIssue::DuplicateOutput {
+                    location: 2,
+                    first: "transform".to_owned(),
+                    second: "uv".to_owned(),
+                },
+                &InterfaceIssue::DuplicateOutput {
+                    location: 5,
+                    first: "weights".to_owned(),
+                    second: "fog".to_owned(),
+                },
+            ]
+        );
+    }
+}
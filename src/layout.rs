@@ -0,0 +1,376 @@
+use crate::error::ReflectError;
+use crate::types::{
+    ReflectBlockVariable, ReflectDecorationFlags, ReflectDescriptorType, ReflectTypeFlags,
+};
+use crate::ReflectModule;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BlockLayout {
+    Std140,
+    Std430,
+    Scalar,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum LayoutViolationKind {
+    Offset,
+    ArrayStride,
+    MatrixStride,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LayoutViolation {
+    pub path: String,
+    pub kind: LayoutViolationKind,
+    pub expected: u32,
+    pub actual: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlockLayoutReport {
+    pub name: String,
+    pub set: Option<u32>,
+    pub binding: Option<u32>,
+    pub std140: Vec<LayoutViolation>,
+    pub std430: Vec<LayoutViolation>,
+    pub scalar: Vec<LayoutViolation>,
+}
+
+impl BlockLayoutReport {
+    pub fn violations(&self, layout: BlockLayout) -> &[LayoutViolation] {
+        match layout {
+            BlockLayout::Std140 => &self.std140,
+            BlockLayout::Std430 => &self.std430,
+            BlockLayout::Scalar => &self.scalar,
+        }
+    }
+
+    pub fn conforms(&self, layout: BlockLayout) -> bool {
+        self.violations(layout).is_empty()
+    }
+
+    pub fn strictest_layout(&self) -> Option<BlockLayout> {
+        [
+            BlockLayout::Std140,
+            BlockLayout::Std430,
+            BlockLayout::Scalar,
+        ]
+        .iter()
+        .copied()
+        .find(|&layout| self.conforms(layout))
+    }
+}
+
+pub fn check_block_layout(block: &ReflectBlockVariable) -> BlockLayoutReport {
+    BlockLayoutReport {
+        name: block.name.clone(),
+        set: None,
+        binding: None,
+        std140: validate_block_layout(block, BlockLayout::Std140),
+        std430: validate_block_layout(block, BlockLayout::Std430),
+        scalar: validate_block_layout(block, BlockLayout::Scalar),
+    }
+}
+
+// Checks uniform and storage buffers and push constant blocks of every entry point.
+pub fn check_module_layouts(
+    module: &impl ReflectModule,
+) -> Result<Vec<BlockLayoutReport>, ReflectError> {
+    let mut reports = Vec::new();
+    for binding in module.enumerate_descriptor_bindings(None)? {
+        match binding.descriptor_type {
+            ReflectDescriptorType::UniformBuffer
+            | ReflectDescriptorType::UniformBufferDynamic
+            | ReflectDescriptorType::StorageBuffer
+            | ReflectDescriptorType::StorageBufferDynamic => {
+                let mut report = check_block_layout(&binding.block);
+                report.name = binding.name.clone();
+                report.set = Some(binding.set);
+                report.binding = Some(binding.binding);
+                reports.push(report);
+            }
+            _ => {}
+        }
+    }
+    for block in module.enumerate_push_constant_blocks(None)? {
+        reports.push(check_block_layout(&block));
+    }
+    Ok(reports)
+}
+
+// Uses the alignment rules of the Vulkan specification ("Offset and Stride Assignment")
+// without the relaxed block layout exceptions for vectors.
+pub fn validate_block_layout(
+    block: &ReflectBlockVariable,
+    layout: BlockLayout,
+) -> Vec<LayoutViolation> {
+    let mut violations = Vec::new();
+    validate_members(&block.members, "", layout, &mut violations);
+    violations
+}
+
+fn validate_members(
+    members: &[ReflectBlockVariable],
+    parent: &str,
+    layout: BlockLayout,
+    violations: &mut Vec<LayoutViolation>,
+) {
+    let mut min_offset = 0;
+    for member in members {
+        let path = if parent.is_empty() {
+            member.name.clone()
+        } else {
+            format!("{}.{}", parent, member.name)
+        };
+        let align = alignment(member, layout);
+        let expected = round_up(min_offset, align);
+        if member.offset < min_offset || member.offset % align != 0 {
+            violations.push(LayoutViolation {
+                path: path.clone(),
+                kind: LayoutViolationKind::Offset,
+                expected,
+                actual: member.offset,
+            });
+        }
+
+        let type_flags = member_type_flags(member);
+        if type_flags.contains(ReflectTypeFlags::MATRIX) {
+            let matrix = &member.numeric.matrix;
+            let vector_align = matrix_vector_alignment(member, layout);
+            let minor_count = if is_row_major(member) {
+                matrix.column_count
+            } else {
+                matrix.row_count
+            };
+            let vector_size = minor_count * scalar_size(member);
+            if matrix.stride % vector_align != 0 || matrix.stride < vector_size {
+                violations.push(LayoutViolation {
+                    path: path.clone(),
+                    kind: LayoutViolationKind::MatrixStride,
+                    expected: round_up(vector_size, vector_align),
+                    actual: matrix.stride,
+                });
+            }
+        }
+        if !member.array.dims.is_empty() && member.array.stride != 0 {
+            let element_size = element_size(member);
+            let stride = member.array.stride;
+            if stride % align != 0 || stride < element_size {
+                violations.push(LayoutViolation {
+                    path: path.clone(),
+                    kind: LayoutViolationKind::ArrayStride,
+                    expected: round_up(element_size, align),
+                    actual: stride,
+                });
+            }
+        }
+        if type_flags.contains(ReflectTypeFlags::STRUCT) {
+            validate_members(&member.members, &path, layout, violations);
+        }
+
+        // Outside of the scalar layout, nothing may be placed between the end of a struct,
+        // array or matrix and the next multiple of its alignment.
+        let end = member.offset + member_size(member);
+        let is_aggregate = type_flags.intersects(
+            ReflectTypeFlags::STRUCT | ReflectTypeFlags::MATRIX | ReflectTypeFlags::ARRAY,
+        );
+        min_offset = if is_aggregate && layout != BlockLayout::Scalar {
+            round_up(end, align)
+        } else {
+            end
+        };
+    }
+}
+
+fn member_type_flags(member: &ReflectBlockVariable) -> ReflectTypeFlags {
+    member
+        .type_description
+        .as_ref()
+        .map(|type_description| type_description.type_flags)
+        .unwrap_or_default()
+}
+
+fn is_row_major(member: &ReflectBlockVariable) -> bool {
+    member
+        .decoration_flags
+        .contains(ReflectDecorationFlags::ROW_MAJOR)
+}
+
+fn scalar_size(member: &ReflectBlockVariable) -> u32 {
+    match member.numeric.scalar.width {
+        0 => 4,
+        width => width / 8,
+    }
+}
+
+fn vector_alignment(component_count: u32, scalar_size: u32, layout: BlockLayout) -> u32 {
+    match (layout, component_count) {
+        (BlockLayout::Scalar, _) | (_, 0) | (_, 1) => scalar_size,
+        (_, 2) => 2 * scalar_size,
+        _ => 4 * scalar_size,
+    }
+}
+
+fn matrix_vector_alignment(member: &ReflectBlockVariable, layout: BlockLayout) -> u32 {
+    let matrix = &member.numeric.matrix;
+    let minor_count = if is_row_major(member) {
+        matrix.column_count
+    } else {
+        matrix.row_count
+    };
+    let align = vector_alignment(minor_count, scalar_size(member), layout);
+    match layout {
+        BlockLayout::Std140 => round_up(align, 16),
+        _ => align,
+    }
+}
+
+fn alignment(member: &ReflectBlockVariable, layout: BlockLayout) -> u32 {
+    let type_flags = member_type_flags(member);
+    let align = if type_flags.contains(ReflectTypeFlags::STRUCT) {
+        let align = member
+            .members
+            .iter()
+            .map(|member| alignment(member, layout))
+            .max()
+            .unwrap_or(1);
+        match layout {
+            BlockLayout::Std140 => round_up(align, 16),
+            _ => align,
+        }
+    } else if type_flags.contains(ReflectTypeFlags::MATRIX) {
+        matrix_vector_alignment(member, layout)
+    } else if type_flags.contains(ReflectTypeFlags::VECTOR) {
+        vector_alignment(
+            member.numeric.vector.component_count,
+            scalar_size(member),
+            layout,
+        )
+    } else {
+        scalar_size(member)
+    };
+    if type_flags.contains(ReflectTypeFlags::ARRAY) && layout == BlockLayout::Std140 {
+        round_up(align, 16)
+    } else {
+        align
+    }
+}
+
+// The size of one element, from its first byte to its last, without trailing padding.
+fn element_size(member: &ReflectBlockVariable) -> u32 {
+    let type_flags = member_type_flags(member);
+    if type_flags.contains(ReflectTypeFlags::STRUCT) {
+        member
+            .members
+            .iter()
+            .map(|member| member.offset + member_size(member))
+            .max()
+            .unwrap_or(0)
+    } else if type_flags.contains(ReflectTypeFlags::MATRIX) {
+        let matrix = &member.numeric.matrix;
+        let (major_count, minor_count) = if is_row_major(member) {
+            (matrix.row_count, matrix.column_count)
+        } else {
+            (matrix.column_count, matrix.row_count)
+        };
+        major_count.saturating_sub(1) * matrix.stride + minor_count * scalar_size(member)
+    } else if type_flags.contains(ReflectTypeFlags::VECTOR) {
+        member.numeric.vector.component_count * scalar_size(member)
+    } else {
+        scalar_size(member)
+    }
+}
+
+fn member_size(member: &ReflectBlockVariable) -> u32 {
+    let element_count: u32 = member.array.dims.iter().product();
+    match element_count {
+        0 => element_size(member),
+        count => (count - 1) * member.array.stride + element_size(member),
+    }
+}
+
+fn round_up(value: u32, align: u32) -> u32 {
+    value.div_ceil(align.max(1)) * align.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{block_member, float};
+    use crate::types::*;
+
+    fn block(members: Vec<ReflectBlockVariable>) -> ReflectBlockVariable {
+        ReflectBlockVariable {
+            name: "Block".to_owned(),
+            members,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn vec3_packing() {
+        let scalar = ReflectTypeFlags::FLOAT;
+        let vec3 = ReflectTypeFlags::FLOAT | ReflectTypeFlags::VECTOR;
+
+        // A float followed by a vec3, packed the way HLSL packs a cbuffer.
+        let report = check_block_layout(&block(vec![
+            block_member("a", 0, scalar, float(0)),
+            block_member("b", 4, vec3, float(3)),
+        ]));
+        assert_eq!(
+            report.std140,
+            vec![LayoutViolation {
+                path: "b".to_owned(),
+                kind: LayoutViolationKind::Offset,
+                expected: 16,
+                actual: 4,
+            }]
+        );
+        assert_eq!(report.std430, report.std140);
+        assert!(report.conforms(BlockLayout::Scalar));
+        assert_eq!(report.strictest_layout(), Some(BlockLayout::Scalar));
+
+        let report = check_block_layout(&block(vec![
+            block_member("a", 0, vec3, float(3)),
+            block_member("b", 12, scalar, float(0)),
+        ]));
+        assert_eq!(report.strictest_layout(), Some(BlockLayout::Std140));
+    }
+
+    #[test]
+    fn array_and_struct_rules() {
+        let scalar = ReflectTypeFlags::FLOAT;
+        let mut weights = block_member("weights", 0, scalar | ReflectTypeFlags::ARRAY, float(0));
+        weights.array = ReflectArrayTraits {
+            dims: vec![4],
+            stride: 4,
+        };
+        let mut light = block_member("light", 16, ReflectTypeFlags::STRUCT, float(0));
+        light.members = vec![block_member("intensity", 0, scalar, float(0))];
+
+        let report = check_block_layout(&block(vec![
+            weights,
+            light,
+            block_member("after", 20, scalar, float(0)),
+        ]));
+        assert_eq!(
+            report.std140,
+            vec![
+                LayoutViolation {
+                    path: "weights".to_owned(),
+                    kind: LayoutViolationKind::ArrayStride,
+                    expected: 16,
+                    actual: 4,
+                },
+                LayoutViolation {
+                    path: "after".to_owned(),
+                    kind: LayoutViolationKind::Offset,
+                    expected: 32,
+                    actual: 20,
+                },
+            ]
+        );
+        assert!(report.conforms(BlockLayout::Std430));
+        assert!(report.conforms(BlockLayout::Scalar));
+    }
+}
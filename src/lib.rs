@@ -4,12 +4,124 @@ extern crate num_traits;
 #[macro_use]
 extern crate serde_derive;
 
+#[cfg(feature = "c-backend")]
 use num_traits::cast::FromPrimitive;
 
+#[cfg(not(any(feature = "c-backend", feature = "pure-rust")))]
+compile_error!("either the `c-backend` or the `pure-rust` feature must be enabled");
+
+pub mod codegen;
+#[cfg_attr(not(feature = "c-backend"), allow(dead_code))]
 pub mod convert;
+pub mod error;
 pub mod ffi;
+#[cfg(test)]
+mod fixtures;
+pub mod interface;
+pub mod layout;
+pub(crate) mod parser;
+pub mod pipeline;
+#[cfg(feature = "pure-rust")]
+pub mod pure;
+pub mod remap;
 pub mod types;
+pub mod vertex;
+#[cfg(feature = "ash")]
+pub mod vulkan;
+#[cfg(feature = "wgpu")]
+pub mod webgpu;
+pub mod writer;
+
+#[cfg(all(feature = "pure-rust", not(feature = "c-backend")))]
+pub use pure::{create_shader_module, ShaderModule};
+
+// The reflection calls used by `pipeline`, `layout` and `interface`, so their helpers accept
+// the module of either backend when both are enabled.
+pub trait ReflectModule {
+    fn get_shader_stage(&self) -> types::ReflectShaderStageFlags;
+    fn enumerate_entry_points(&self) -> Result<Vec<types::ReflectEntryPoint>, error::ReflectError>;
+    fn enumerate_input_variables(
+        &self,
+        entry_point: Option<&str>,
+    ) -> Result<Vec<types::ReflectInterfaceVariable>, error::ReflectError>;
+    fn enumerate_output_variables(
+        &self,
+        entry_point: Option<&str>,
+    ) -> Result<Vec<types::ReflectInterfaceVariable>, error::ReflectError>;
+    fn enumerate_descriptor_bindings(
+        &self,
+        entry_point: Option<&str>,
+    ) -> Result<Vec<types::ReflectDescriptorBinding>, error::ReflectError>;
+    fn enumerate_descriptor_sets(
+        &self,
+        entry_point: Option<&str>,
+    ) -> Result<Vec<types::ReflectDescriptorSet>, error::ReflectError>;
+    fn enumerate_push_constant_blocks(
+        &self,
+        entry_point: Option<&str>,
+    ) -> Result<Vec<types::ReflectBlockVariable>, error::ReflectError>;
+}
 
+// Forwards to the inherent methods of `$module`, which `$ty` is or derefs to.
+macro_rules! impl_reflect_module {
+    ($ty:ty, $module:path) => {
+        impl ReflectModule for $ty {
+            fn get_shader_stage(&self) -> types::ReflectShaderStageFlags {
+                <$module>::get_shader_stage(self)
+            }
+
+            fn enumerate_entry_points(
+                &self,
+            ) -> Result<Vec<types::ReflectEntryPoint>, error::ReflectError> {
+                <$module>::enumerate_entry_points(self)
+            }
+
+            fn enumerate_input_variables(
+                &self,
+                entry_point: Option<&str>,
+            ) -> Result<Vec<types::ReflectInterfaceVariable>, error::ReflectError> {
+                <$module>::enumerate_input_variables(self, entry_point)
+            }
+
+            fn enumerate_output_variables(
+                &self,
+                entry_point: Option<&str>,
+            ) -> Result<Vec<types::ReflectInterfaceVariable>, error::ReflectError> {
+                <$module>::enumerate_output_variables(self, entry_point)
+            }
+
+            fn enumerate_descriptor_bindings(
+                &self,
+                entry_point: Option<&str>,
+            ) -> Result<Vec<types::ReflectDescriptorBinding>, error::ReflectError> {
+                <$module>::enumerate_descriptor_bindings(self, entry_point)
+            }
+
+            fn enumerate_descriptor_sets(
+                &self,
+                entry_point: Option<&str>,
+            ) -> Result<Vec<types::ReflectDescriptorSet>, error::ReflectError> {
+                <$module>::enumerate_descriptor_sets(self, entry_point)
+            }
+
+            fn enumerate_push_constant_blocks(
+                &self,
+                entry_point: Option<&str>,
+            ) -> Result<Vec<types::ReflectBlockVariable>, error::ReflectError> {
+                <$module>::enumerate_push_constant_blocks(self, entry_point)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "c-backend")]
+impl_reflect_module!(ShaderModule, ShaderModule);
+#[cfg(feature = "c-backend")]
+impl_reflect_module!(ShaderModuleRef<'_>, ShaderModule);
+#[cfg(feature = "pure-rust")]
+impl_reflect_module!(pure::ShaderModule, pure::ShaderModule);
+
+#[cfg_attr(not(feature = "c-backend"), allow(dead_code))]
 pub(crate) fn ffi_to_string(ffi: *const ::std::os::raw::c_char) -> String {
     if ffi.is_null() {
         String::new()
@@ -30,24 +142,35 @@ impl Default for ffi::SpvReflectDescriptorSet {
     }
 }
 
-#[derive(Default, Clone)]
+#[cfg(feature = "c-backend")]
+#[derive(Default)]
 pub struct ShaderModule {
     module: Option<ffi::SpvReflectShaderModule>,
 }
 
+#[cfg(feature = "c-backend")]
 impl ShaderModule {
-    pub fn load_u8_data(spv_data: &[u8]) -> Result<ShaderModule, &'static str> {
-        Ok(create_shader_module(spv_data)?)
+    pub fn load_u8_data(spv_data: &[u8]) -> Result<ShaderModule, error::ReflectError> {
+        create_shader_module(spv_data)
     }
 
-    pub fn load_u32_data(spv_data: &[u32]) -> Result<ShaderModule, &'static str> {
+    pub fn load_u32_data(spv_data: &[u32]) -> Result<ShaderModule, error::ReflectError> {
         let u8_data: &[u8] = unsafe {
             std::slice::from_raw_parts(
                 spv_data.as_ptr() as *const u8,
-                spv_data.len() * std::mem::size_of::<u32>(),
+                std::mem::size_of_val(spv_data),
             )
         };
-        Ok(create_shader_module(u8_data)?)
+        create_shader_module(u8_data)
+    }
+
+    // The FFI module owns heap allocations that are released in `drop`, so a copy is
+    // re-created from the (possibly modified) SPIR-V code, which can fail.
+    pub fn try_clone(&self) -> Result<ShaderModule, error::ReflectError> {
+        match self.module {
+            Some(_) => ShaderModule::load_u32_data(&self.get_code()),
+            None => Ok(ShaderModule::default()),
+        }
     }
 
     pub fn get_code(&self) -> Vec<u32> {
@@ -70,6 +193,26 @@ impl ShaderModule {
         }
     }
 
+    pub fn enumerate_capabilities(
+        &self,
+    ) -> Result<Vec<types::ReflectCapability>, error::ReflectError> {
+        if let Some(ref module) = self.module {
+            let ffi_capabilities = unsafe {
+                convert::ffi_slice_from_raw_parts(
+                    module.capabilities,
+                    module.capability_count as usize,
+                )
+            };
+            let capabilities: Vec<types::ReflectCapability> = ffi_capabilities
+                .iter()
+                .map(convert::ffi_to_capability)
+                .collect();
+            Ok(capabilities)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
     pub fn get_shader_stage(&self) -> types::ReflectShaderStageFlags {
         match self.module {
             Some(ref module) => convert::ffi_to_shader_stage_flags(module.shader_stage),
@@ -79,12 +222,10 @@ impl ShaderModule {
 
     pub fn get_source_language(&self) -> spirv::SourceLanguage {
         match self.module {
-            Some(ref module) => {
-                match spirv::SourceLanguage::from_u32(module.source_language as u32) {
-                    Some(language) => language,
-                    None => spirv::SourceLanguage::Unknown,
-                }
-            }
+            Some(ref module) => match spirv::SourceLanguage::from_u32(module.source_language) {
+                Some(language) => language,
+                None => spirv::SourceLanguage::Unknown,
+            },
             None => spirv::SourceLanguage::Unknown,
         }
     }
@@ -113,7 +254,7 @@ impl ShaderModule {
     pub fn get_spirv_execution_model(&self) -> spirv::ExecutionModel {
         match self.module {
             Some(ref module) => {
-                match spirv::ExecutionModel::from_u32(module.spirv_execution_model as u32) {
+                match spirv::ExecutionModel::from_u32(module.spirv_execution_model) {
                     Some(model) => model,
                     None => spirv::ExecutionModel::Vertex,
                 }
@@ -125,7 +266,7 @@ impl ShaderModule {
     pub fn enumerate_input_variables(
         &self,
         entry_point: Option<&str>,
-    ) -> Result<Vec<types::ReflectInterfaceVariable>, &'static str> {
+    ) -> Result<Vec<types::ReflectInterfaceVariable>, error::ReflectError> {
         if let Some(ref module) = self.module {
             let mut count: u32 = 0;
             let result = unsafe {
@@ -176,7 +317,7 @@ impl ShaderModule {
                             .collect();
                         Ok(vars)
                     }
-                    _ => Err(convert::result_to_string(result)),
+                    _ => Err(convert::result_to_error(result).with_entry_point(entry_point)),
                 }
             } else {
                 Ok(Vec::new())
@@ -189,7 +330,7 @@ impl ShaderModule {
     pub fn enumerate_output_variables(
         &self,
         entry_point: Option<&str>,
-    ) -> Result<Vec<types::ReflectInterfaceVariable>, &'static str> {
+    ) -> Result<Vec<types::ReflectInterfaceVariable>, error::ReflectError> {
         if let Some(ref module) = self.module {
             let mut count: u32 = 0;
             let result = unsafe {
@@ -240,7 +381,7 @@ impl ShaderModule {
                             .collect();
                         Ok(vars)
                     }
-                    _ => Err(convert::result_to_string(result)),
+                    _ => Err(convert::result_to_error(result).with_entry_point(entry_point)),
                 }
             } else {
                 Ok(Vec::new())
@@ -250,10 +391,181 @@ impl ShaderModule {
         }
     }
 
+    pub fn enumerate_interface_variables(
+        &self,
+        entry_point: Option<&str>,
+    ) -> Result<Vec<types::ReflectInterfaceVariable>, error::ReflectError> {
+        let entry_point_cstr = entry_point
+            .map(|name| name_to_cstring(name, entry_point))
+            .transpose()?;
+        if let Some(ref module) = self.module {
+            let mut count: u32 = 0;
+            let result = unsafe {
+                match entry_point_cstr {
+                    Some(ref entry_point_cstr) => {
+                        ffi::spvReflectEnumerateEntryPointInterfaceVariables(
+                            module,
+                            entry_point_cstr.as_ptr(),
+                            &mut count,
+                            ::std::ptr::null_mut(),
+                        )
+                    }
+                    None => ffi::spvReflectEnumerateInterfaceVariables(
+                        module,
+                        &mut count,
+                        ::std::ptr::null_mut(),
+                    ),
+                }
+            };
+            if result == ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS && count > 0 {
+                let mut ffi_vars: Vec<*mut ffi::SpvReflectInterfaceVariable> =
+                    vec![::std::ptr::null_mut(); count as usize];
+                let result = unsafe {
+                    let mut out_count: u32 = count;
+                    match entry_point_cstr {
+                        Some(ref entry_point_cstr) => {
+                            ffi::spvReflectEnumerateEntryPointInterfaceVariables(
+                                module,
+                                entry_point_cstr.as_ptr(),
+                                &mut out_count,
+                                ffi_vars.as_mut_ptr(),
+                            )
+                        }
+                        None => ffi::spvReflectEnumerateInterfaceVariables(
+                            module,
+                            &mut out_count,
+                            ffi_vars.as_mut_ptr(),
+                        ),
+                    }
+                };
+                match result {
+                    ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS => {
+                        let vars: Vec<types::ReflectInterfaceVariable> = ffi_vars
+                            .iter()
+                            .map(|&var| convert::ffi_to_interface_variable(var))
+                            .collect();
+                        Ok(vars)
+                    }
+                    _ => Err(convert::result_to_error(result).with_entry_point(entry_point)),
+                }
+            } else {
+                Ok(Vec::new())
+            }
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    pub fn get_input_variable_by_location(
+        &self,
+        entry_point: Option<&str>,
+        location: u32,
+    ) -> Result<types::ReflectInterfaceVariable, error::ReflectError> {
+        let module = self.module.as_ref().ok_or_else(element_not_found)?;
+        let entry_point_cstr = entry_point
+            .map(|name| name_to_cstring(name, entry_point))
+            .transpose()?;
+        let mut result = ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS;
+        let var = unsafe {
+            match entry_point_cstr {
+                Some(ref entry_point_cstr) => ffi::spvReflectGetEntryPointInputVariableByLocation(
+                    module,
+                    entry_point_cstr.as_ptr(),
+                    location,
+                    &mut result,
+                ),
+                None => ffi::spvReflectGetInputVariableByLocation(module, location, &mut result),
+            }
+        };
+        ffi_to_interface_variable_result(var, result, entry_point)
+    }
+
+    pub fn get_output_variable_by_location(
+        &self,
+        entry_point: Option<&str>,
+        location: u32,
+    ) -> Result<types::ReflectInterfaceVariable, error::ReflectError> {
+        let module = self.module.as_ref().ok_or_else(element_not_found)?;
+        let entry_point_cstr = entry_point
+            .map(|name| name_to_cstring(name, entry_point))
+            .transpose()?;
+        let mut result = ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS;
+        let var = unsafe {
+            match entry_point_cstr {
+                Some(ref entry_point_cstr) => ffi::spvReflectGetEntryPointOutputVariableByLocation(
+                    module,
+                    entry_point_cstr.as_ptr(),
+                    location,
+                    &mut result,
+                ),
+                None => ffi::spvReflectGetOutputVariableByLocation(module, location, &mut result),
+            }
+        };
+        ffi_to_interface_variable_result(var, result, entry_point)
+    }
+
+    pub fn get_input_variable_by_semantic(
+        &self,
+        entry_point: Option<&str>,
+        semantic: &str,
+    ) -> Result<types::ReflectInterfaceVariable, error::ReflectError> {
+        let module = self.module.as_ref().ok_or_else(element_not_found)?;
+        let entry_point_cstr = entry_point
+            .map(|name| name_to_cstring(name, entry_point))
+            .transpose()?;
+        let semantic_cstr = name_to_cstring(semantic, entry_point)?;
+        let mut result = ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS;
+        let var = unsafe {
+            match entry_point_cstr {
+                Some(ref entry_point_cstr) => ffi::spvReflectGetEntryPointInputVariableBySemantic(
+                    module,
+                    entry_point_cstr.as_ptr(),
+                    semantic_cstr.as_ptr(),
+                    &mut result,
+                ),
+                None => ffi::spvReflectGetInputVariableBySemantic(
+                    module,
+                    semantic_cstr.as_ptr(),
+                    &mut result,
+                ),
+            }
+        };
+        ffi_to_interface_variable_result(var, result, entry_point)
+    }
+
+    pub fn get_output_variable_by_semantic(
+        &self,
+        entry_point: Option<&str>,
+        semantic: &str,
+    ) -> Result<types::ReflectInterfaceVariable, error::ReflectError> {
+        let module = self.module.as_ref().ok_or_else(element_not_found)?;
+        let entry_point_cstr = entry_point
+            .map(|name| name_to_cstring(name, entry_point))
+            .transpose()?;
+        let semantic_cstr = name_to_cstring(semantic, entry_point)?;
+        let mut result = ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS;
+        let var = unsafe {
+            match entry_point_cstr {
+                Some(ref entry_point_cstr) => ffi::spvReflectGetEntryPointOutputVariableBySemantic(
+                    module,
+                    entry_point_cstr.as_ptr(),
+                    semantic_cstr.as_ptr(),
+                    &mut result,
+                ),
+                None => ffi::spvReflectGetOutputVariableBySemantic(
+                    module,
+                    semantic_cstr.as_ptr(),
+                    &mut result,
+                ),
+            }
+        };
+        ffi_to_interface_variable_result(var, result, entry_point)
+    }
+
     pub fn enumerate_descriptor_bindings(
         &self,
         entry_point: Option<&str>,
-    ) -> Result<Vec<types::ReflectDescriptorBinding>, &'static str> {
+    ) -> Result<Vec<types::ReflectDescriptorBinding>, error::ReflectError> {
         if let Some(ref module) = self.module {
             let mut count: u32 = 0;
             let result = unsafe {
@@ -304,7 +616,7 @@ impl ShaderModule {
                             .collect();
                         Ok(bindings)
                     }
-                    _ => Err(convert::result_to_string(result)),
+                    _ => Err(convert::result_to_error(result).with_entry_point(entry_point)),
                 }
             } else {
                 Ok(Vec::new())
@@ -317,7 +629,7 @@ impl ShaderModule {
     pub fn enumerate_descriptor_sets(
         &self,
         entry_point: Option<&str>,
-    ) -> Result<Vec<types::ReflectDescriptorSet>, &'static str> {
+    ) -> Result<Vec<types::ReflectDescriptorSet>, error::ReflectError> {
         if let Some(ref module) = self.module {
             let mut count: u32 = 0;
             let result = unsafe {
@@ -365,7 +677,7 @@ impl ShaderModule {
                         .iter()
                         .map(|&set| convert::ffi_to_descriptor_set(set))
                         .collect()),
-                    _ => Err(convert::result_to_string(result)),
+                    _ => Err(convert::result_to_error(result).with_entry_point(entry_point)),
                 }
             } else {
                 Ok(Vec::new())
@@ -375,10 +687,33 @@ impl ShaderModule {
         }
     }
 
+    // Only the bindings an entry point statically uses. Without an entry point, that's any
+    // entry point of the module.
+    pub fn enumerate_accessed_descriptor_bindings(
+        &self,
+        entry_point: Option<&str>,
+    ) -> Result<Vec<types::ReflectDescriptorBinding>, error::ReflectError> {
+        let mut bindings = self.enumerate_descriptor_bindings(entry_point)?;
+        bindings.retain(|binding| binding.accessed);
+        Ok(bindings)
+    }
+
+    pub fn enumerate_accessed_descriptor_sets(
+        &self,
+        entry_point: Option<&str>,
+    ) -> Result<Vec<types::ReflectDescriptorSet>, error::ReflectError> {
+        let mut sets = self.enumerate_descriptor_sets(entry_point)?;
+        for set in &mut sets {
+            set.bindings.retain(|binding| binding.accessed);
+        }
+        sets.retain(|set| !set.bindings.is_empty());
+        Ok(sets)
+    }
+
     pub fn enumerate_push_constant_blocks(
         &self,
         entry_point: Option<&str>,
-    ) -> Result<Vec<types::ReflectBlockVariable>, &'static str> {
+    ) -> Result<Vec<types::ReflectBlockVariable>, error::ReflectError> {
         if let Some(ref module) = self.module {
             let mut count: u32 = 0;
             let result = unsafe {
@@ -429,7 +764,7 @@ impl ShaderModule {
                             .collect();
                         Ok(blocks)
                     }
-                    _ => Err(convert::result_to_string(result)),
+                    _ => Err(convert::result_to_error(result).with_entry_point(entry_point)),
                 }
             } else {
                 Ok(Vec::new())
@@ -439,14 +774,72 @@ impl ShaderModule {
         }
     }
 
-    pub fn enumerate_entry_points(&self) -> Result<Vec<types::ReflectEntryPoint>, &'static str> {
+    pub fn enumerate_specialization_constants(
+        &self,
+    ) -> Result<Vec<types::ReflectSpecializationConstant>, error::ReflectError> {
+        if let Some(ref module) = self.module {
+            let mut count: u32 = 0;
+            let result = unsafe {
+                ffi::spvReflectEnumerateSpecializationConstants(
+                    module,
+                    &mut count,
+                    ::std::ptr::null_mut(),
+                )
+            };
+            if result == ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS && count > 0 {
+                let mut ffi_constants: Vec<*mut ffi::SpvReflectSpecializationConstant> =
+                    vec![::std::ptr::null_mut(); count as usize];
+                let result = unsafe {
+                    let mut out_count: u32 = count;
+                    ffi::spvReflectEnumerateSpecializationConstants(
+                        module,
+                        &mut out_count,
+                        ffi_constants.as_mut_ptr(),
+                    )
+                };
+                match result {
+                    ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS => {
+                        let defaults =
+                            parser::parse_specialization_constant_defaults(&self.get_code());
+                        let constants: Vec<types::ReflectSpecializationConstant> = ffi_constants
+                            .iter()
+                            .map(|&constant| {
+                                let constant = unsafe { &*constant };
+                                convert::ffi_to_specialization_constant(
+                                    constant,
+                                    defaults.get(&constant.spirv_id),
+                                )
+                            })
+                            .collect();
+                        Ok(constants)
+                    }
+                    _ => Err(convert::result_to_error(result)),
+                }
+            } else {
+                Ok(Vec::new())
+            }
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    pub fn enumerate_entry_points(
+        &self,
+    ) -> Result<Vec<types::ReflectEntryPoint>, error::ReflectError> {
         if let Some(ref module) = self.module {
             let ffi_entry_points = unsafe {
                 std::slice::from_raw_parts(module.entry_points, module.entry_point_count as usize)
             };
+            let execution_modes = parser::parse_execution_modes(&self.get_code());
             let entry_points: Vec<types::ReflectEntryPoint> = ffi_entry_points
                 .iter()
-                .map(|entry_point| convert::ffi_to_entry_point(entry_point))
+                .map(|ffi_entry_point| {
+                    let mut entry_point = convert::ffi_to_entry_point(ffi_entry_point);
+                    if let Some(modes) = execution_modes.get(&entry_point.id) {
+                        entry_point.execution_modes = modes.clone();
+                    }
+                    entry_point
+                })
                 .collect();
             Ok(entry_points)
         } else {
@@ -466,44 +859,98 @@ impl ShaderModule {
         binding: &types::descriptor::ReflectDescriptorBinding,
         new_binding: u32,
         new_set: Option<u32>,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), error::ReflectError> {
         match self.module {
             Some(ref mut module) => {
+                let ffi_binding = match find_descriptor_binding(module, binding) {
+                    Some(ffi_binding) => ffi_binding,
+                    None => {
+                        return Err(element_not_found()
+                            .with_binding(binding.set, binding.binding)
+                            .with_word_offset(binding.word_offset.0))
+                    }
+                };
                 let new_set = new_set.unwrap_or(ffi::SPV_REFLECT_SET_NUMBER_DONT_CHANGE as u32);
                 let result = unsafe {
                     ffi::spvReflectChangeDescriptorBindingNumbers(
                         module as *mut ffi::SpvReflectShaderModule,
-                        binding.internal_data,
+                        ffi_binding,
                         new_binding,
                         new_set,
                     )
                 };
                 match result {
                     ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS => Ok(()),
-                    _ => Err(convert::result_to_string(result)),
+                    _ => Err(convert::result_to_error(result)
+                        .with_binding(binding.set, binding.binding)
+                        .with_word_offset(binding.word_offset.0)),
                 }
             }
             None => Ok(()),
         }
     }
 
+    // Patches all bindings in a copy of the code and reflects it once, rather than letting
+    // SPIRV-Reflect rewrite and re-sort the module for every binding.
+    pub(crate) fn patch_descriptor_bindings(
+        &mut self,
+        changes: &[(&types::ReflectDescriptorBinding, u32, u32)],
+    ) -> Result<(), error::ReflectError> {
+        let module = match self.module {
+            Some(ref module) => module,
+            None => return Ok(()),
+        };
+        let mut code = self.get_code();
+        for &(binding, new_binding, new_set) in changes {
+            let word_offset = match find_descriptor_binding(module, binding) {
+                Some(ffi_binding) => unsafe { (*ffi_binding).word_offset },
+                None => {
+                    return Err(element_not_found()
+                        .with_binding(binding.set, binding.binding)
+                        .with_word_offset(binding.word_offset.0))
+                }
+            };
+            for &(offset, value) in &[
+                (word_offset.binding, new_binding),
+                (word_offset.set, new_set),
+            ] {
+                match code.get_mut(offset as usize) {
+                    Some(word) => *word = value,
+                    None => {
+                        return Err(error::ReflectError::new(
+                            error::ReflectErrorKind::RangeExceeded,
+                        )
+                        .with_binding(binding.set, binding.binding)
+                        .with_word_offset(offset))
+                    }
+                }
+            }
+        }
+        *self = ShaderModule::load_u32_data(&code)?;
+        Ok(())
+    }
+
     pub fn change_descriptor_set_number(
         &mut self,
         set: &types::descriptor::ReflectDescriptorSet,
         new_set: u32,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), error::ReflectError> {
         match self.module {
             Some(ref mut module) => {
+                let ffi_set = match find_descriptor_set(module, set) {
+                    Some(ffi_set) => ffi_set,
+                    None => return Err(element_not_found().with_set(set.set)),
+                };
                 let result = unsafe {
                     ffi::spvReflectChangeDescriptorSetNumber(
                         module as *mut ffi::SpvReflectShaderModule,
-                        set.internal_data,
+                        ffi_set,
                         new_set,
                     )
                 };
                 match result {
                     ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS => Ok(()),
-                    _ => Err(convert::result_to_string(result)),
+                    _ => Err(convert::result_to_error(result).with_set(set.set)),
                 }
             }
             None => Ok(()),
@@ -514,19 +961,29 @@ impl ShaderModule {
         &mut self,
         variable: &types::variable::ReflectInterfaceVariable,
         new_location: u32,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), error::ReflectError> {
         match self.module {
             Some(ref mut module) => {
+                let ffi_variable = match find_interface_variable(
+                    module.input_variables,
+                    module.input_variable_count,
+                    variable,
+                ) {
+                    Some(ffi_variable) => ffi_variable,
+                    None => return Err(element_not_found().with_word_offset(variable.word_offset)),
+                };
                 let result = unsafe {
                     ffi::spvReflectChangeInputVariableLocation(
                         module as *mut ffi::SpvReflectShaderModule,
-                        variable.internal_data,
+                        ffi_variable,
                         new_location,
                     )
                 };
                 match result {
                     ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS => Ok(()),
-                    _ => Err(convert::result_to_string(result)),
+                    _ => {
+                        Err(convert::result_to_error(result).with_word_offset(variable.word_offset))
+                    }
                 }
             }
             None => Ok(()),
@@ -537,19 +994,29 @@ impl ShaderModule {
         &mut self,
         variable: &types::variable::ReflectInterfaceVariable,
         new_location: u32,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), error::ReflectError> {
         match self.module {
             Some(ref mut module) => {
+                let ffi_variable = match find_interface_variable(
+                    module.output_variables,
+                    module.output_variable_count,
+                    variable,
+                ) {
+                    Some(ffi_variable) => ffi_variable,
+                    None => return Err(element_not_found().with_word_offset(variable.word_offset)),
+                };
                 let result = unsafe {
                     ffi::spvReflectChangeOutputVariableLocation(
                         module as *mut ffi::SpvReflectShaderModule,
-                        variable.internal_data,
+                        ffi_variable,
                         new_location,
                     )
                 };
                 match result {
                     ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS => Ok(()),
-                    _ => Err(convert::result_to_string(result)),
+                    _ => {
+                        Err(convert::result_to_error(result).with_word_offset(variable.word_offset))
+                    }
                 }
             }
             None => Ok(()),
@@ -557,6 +1024,102 @@ impl ShaderModule {
     }
 }
 
+#[cfg(feature = "c-backend")]
+fn element_not_found() -> error::ReflectError {
+    convert::result_to_error(ffi::SpvReflectResult_SPV_REFLECT_RESULT_ERROR_ELEMENT_NOT_FOUND)
+}
+
+#[cfg(feature = "c-backend")]
+// A name with an interior NUL can't name anything in the module.
+fn name_to_cstring(
+    name: &str,
+    entry_point: Option<&str>,
+) -> Result<std::ffi::CString, error::ReflectError> {
+    std::ffi::CString::new(name).map_err(|_| element_not_found().with_entry_point(entry_point))
+}
+
+#[cfg(feature = "c-backend")]
+// Reflected values are plain copies of the module data, so the mutation APIs look up the
+// matching FFI element by SPIR-V id and current numbering instead of trusting a pointer.
+fn find_descriptor_binding(
+    module: &ffi::SpvReflectShaderModule,
+    binding: &types::ReflectDescriptorBinding,
+) -> Option<*const ffi::SpvReflectDescriptorBinding> {
+    let ffi_bindings = unsafe {
+        convert::ffi_slice_from_raw_parts(
+            module.descriptor_bindings,
+            module.descriptor_binding_count as usize,
+        )
+    };
+    ffi_bindings
+        .iter()
+        .find(|ffi_binding| {
+            ffi_binding.spirv_id == binding.spirv_id
+                && ffi_binding.binding == binding.binding
+                && ffi_binding.set == binding.set
+        })
+        .map(|ffi_binding| ffi_binding as *const ffi::SpvReflectDescriptorBinding)
+}
+
+#[cfg(feature = "c-backend")]
+fn find_descriptor_set(
+    module: &ffi::SpvReflectShaderModule,
+    set: &types::ReflectDescriptorSet,
+) -> Option<*const ffi::SpvReflectDescriptorSet> {
+    let set_count = (module.descriptor_set_count as usize).min(module.descriptor_sets.len());
+    module.descriptor_sets[..set_count]
+        .iter()
+        .find(|ffi_set| {
+            let ffi_bindings = unsafe {
+                convert::ffi_slice_from_raw_parts(ffi_set.bindings, ffi_set.binding_count as usize)
+            };
+            ffi_set.set == set.set
+                && ffi_bindings.len() == set.bindings.len()
+                && ffi_bindings
+                    .iter()
+                    .zip(&set.bindings)
+                    .all(|(&ffi_binding, binding)| {
+                        binding.spirv_id == unsafe { (*ffi_binding).spirv_id }
+                    })
+        })
+        .map(|ffi_set| ffi_set as *const ffi::SpvReflectDescriptorSet)
+}
+
+#[cfg(feature = "c-backend")]
+fn find_interface_variable(
+    ffi_variables: *mut *mut ffi::SpvReflectInterfaceVariable,
+    ffi_variable_count: u32,
+    variable: &types::ReflectInterfaceVariable,
+) -> Option<*const ffi::SpvReflectInterfaceVariable> {
+    let ffi_variables =
+        unsafe { convert::ffi_slice_from_raw_parts(ffi_variables, ffi_variable_count as usize) };
+    ffi_variables
+        .iter()
+        .find(|&&ffi_variable| {
+            let ffi_variable = unsafe { &*ffi_variable };
+            ffi_variable.spirv_id == variable.spirv_id && ffi_variable.location == variable.location
+        })
+        .map(|&ffi_variable| ffi_variable as *const ffi::SpvReflectInterfaceVariable)
+}
+
+#[cfg(feature = "c-backend")]
+fn ffi_to_interface_variable_result(
+    var: *const ffi::SpvReflectInterfaceVariable,
+    result: ffi::SpvReflectResult,
+    entry_point: Option<&str>,
+) -> Result<types::ReflectInterfaceVariable, error::ReflectError> {
+    match result {
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS if !var.is_null() => {
+            Ok(convert::ffi_to_interface_variable(var))
+        }
+        ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS => {
+            Err(element_not_found().with_entry_point(entry_point))
+        }
+        _ => Err(convert::result_to_error(result).with_entry_point(entry_point)),
+    }
+}
+
+#[cfg(feature = "c-backend")]
 impl Drop for ShaderModule {
     fn drop(&mut self) {
         if let Some(ref mut module) = self.module {
@@ -567,6 +1130,48 @@ impl Drop for ShaderModule {
     }
 }
 
+// SPIRV-Reflect keeps a pointer to the caller's code instead of copying it. The module is
+// only handed out through `Deref`, so the `change_*` methods (which would write into the
+// borrowed code) are unreachable.
+#[cfg(feature = "c-backend")]
+pub struct ShaderModuleRef<'a> {
+    module: ShaderModule,
+    code: std::marker::PhantomData<&'a [u32]>,
+}
+
+#[cfg(feature = "c-backend")]
+impl<'a> ShaderModuleRef<'a> {
+    pub fn load_u32_data(spv_data: &'a [u32]) -> Result<ShaderModuleRef<'a>, error::ReflectError> {
+        let mut module: ffi::SpvReflectShaderModule = unsafe { std::mem::zeroed() };
+        let result: ffi::SpvReflectResult = unsafe {
+            ffi::spvReflectCreateShaderModule2(
+                ffi::SpvReflectModuleFlagBits_SPV_REFLECT_MODULE_FLAG_NO_COPY,
+                std::mem::size_of_val(spv_data),
+                spv_data.as_ptr() as *const std::os::raw::c_void,
+                &mut module,
+            )
+        };
+        match result {
+            ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS => Ok(ShaderModuleRef {
+                module: ShaderModule {
+                    module: Some(module),
+                },
+                code: std::marker::PhantomData,
+            }),
+            _ => Err(convert::result_to_error(result)),
+        }
+    }
+}
+
+#[cfg(feature = "c-backend")]
+impl<'a> std::ops::Deref for ShaderModuleRef<'a> {
+    type Target = ShaderModule;
+
+    fn deref(&self) -> &ShaderModule {
+        &self.module
+    }
+}
+
 /*
 impl From<&[u8]> for ShaderModule {
     fn from(spv_data: &[u8]) -> Result<ShaderModule, &str> {
@@ -576,12 +1181,13 @@ impl From<&[u8]> for ShaderModule {
 */
 
 /*impl<'a, T: AsRef<[u8]>> From<T> for ShaderModule {
-    fn from(v: T) -> Result<ShaderModule, &'static str> {
+    fn from(v: T) -> Result<ShaderModule, error::ReflectError> {
         Ok(create_shader_module(v.as_ref())?)
     }
 }*/
 
-pub fn create_shader_module(spv_data: &[u8]) -> Result<ShaderModule, &'static str> {
+#[cfg(feature = "c-backend")]
+pub fn create_shader_module(spv_data: &[u8]) -> Result<ShaderModule, error::ReflectError> {
     let mut module: ffi::SpvReflectShaderModule = unsafe { std::mem::zeroed() };
     let result: ffi::SpvReflectResult = unsafe {
         ffi::spvReflectCreateShaderModule(
@@ -594,6 +1200,6 @@ pub fn create_shader_module(spv_data: &[u8]) -> Result<ShaderModule, &'static st
         ffi::SpvReflectResult_SPV_REFLECT_RESULT_SUCCESS => Ok(ShaderModule {
             module: Some(module),
         }),
-        _ => Err(convert::result_to_string(result)),
+        _ => Err(convert::result_to_error(result)),
     }
 }
@@ -0,0 +1,352 @@
+use crate::types::*;
+use num_traits::cast::FromPrimitive;
+use std::collections::HashMap;
+
+pub(crate) const SPIRV_MAGIC_NUMBER: u32 = 0x0723_0203;
+pub(crate) const SPIRV_HEADER_WORD_COUNT: usize = 5;
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Instruction<'a> {
+    pub(crate) opcode: u32,
+    pub(crate) operands: &'a [u32],
+}
+
+pub(crate) struct Instructions<'a> {
+    code: &'a [u32],
+    offset: usize,
+}
+
+impl<'a> Iterator for Instructions<'a> {
+    type Item = Instruction<'a>;
+
+    fn next(&mut self) -> Option<Instruction<'a>> {
+        let first_word = *self.code.get(self.offset)?;
+        let word_count = (first_word >> 16) as usize;
+        if word_count == 0 || self.offset + word_count > self.code.len() {
+            self.offset = self.code.len();
+            return None;
+        }
+        let instruction = Instruction {
+            opcode: first_word & 0xFFFF,
+            operands: &self.code[self.offset + 1..self.offset + word_count],
+        };
+        self.offset += word_count;
+        Some(instruction)
+    }
+}
+
+pub(crate) fn instructions(code: &[u32]) -> Instructions<'_> {
+    let offset = if code.len() >= SPIRV_HEADER_WORD_COUNT && code[0] == SPIRV_MAGIC_NUMBER {
+        SPIRV_HEADER_WORD_COUNT
+    } else {
+        code.len()
+    };
+    Instructions { code, offset }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub(crate) struct SpecializationConstantDefault {
+    pub(crate) type_flags: ReflectTypeFlags,
+    pub(crate) scalar: ReflectNumericTraitsScalar,
+    pub(crate) value: ReflectSpecializationConstantValue,
+}
+
+pub(crate) fn parse_specialization_constant_defaults(
+    code: &[u32],
+) -> HashMap<u32, SpecializationConstantDefault> {
+    let mut scalar_types: HashMap<u32, (ReflectTypeFlags, ReflectNumericTraitsScalar)> =
+        HashMap::new();
+    let mut defaults = HashMap::new();
+    for instruction in instructions(code) {
+        let operands = instruction.operands;
+        match instruction.opcode {
+            op if op == spirv::Op::TypeBool as u32 && !operands.is_empty() => {
+                scalar_types.insert(
+                    operands[0],
+                    (
+                        ReflectTypeFlags::BOOL,
+                        ReflectNumericTraitsScalar::default(),
+                    ),
+                );
+            }
+            op if op == spirv::Op::TypeInt as u32 && operands.len() >= 3 => {
+                scalar_types.insert(
+                    operands[0],
+                    (
+                        ReflectTypeFlags::INT,
+                        ReflectNumericTraitsScalar {
+                            width: operands[1],
+                            signedness: operands[2],
+                        },
+                    ),
+                );
+            }
+            op if op == spirv::Op::TypeFloat as u32 && operands.len() >= 2 => {
+                scalar_types.insert(
+                    operands[0],
+                    (
+                        ReflectTypeFlags::FLOAT,
+                        ReflectNumericTraitsScalar {
+                            width: operands[1],
+                            signedness: 0,
+                        },
+                    ),
+                );
+            }
+            op if (op == spirv::Op::SpecConstantTrue as u32
+                || op == spirv::Op::SpecConstantFalse as u32
+                || op == spirv::Op::SpecConstant as u32)
+                && operands.len() >= 2 =>
+            {
+                let (type_flags, scalar) = match scalar_types.get(&operands[0]) {
+                    Some(&scalar_type) => scalar_type,
+                    None => continue,
+                };
+                let value = if op == spirv::Op::SpecConstantTrue as u32 {
+                    ReflectSpecializationConstantValue::Bool(true)
+                } else if op == spirv::Op::SpecConstantFalse as u32 {
+                    ReflectSpecializationConstantValue::Bool(false)
+                } else {
+                    decode_scalar_literal(type_flags, scalar, &operands[2..])
+                };
+                defaults.insert(
+                    operands[1],
+                    SpecializationConstantDefault {
+                        type_flags,
+                        scalar,
+                        value,
+                    },
+                );
+            }
+            _ => {}
+        }
+    }
+    defaults
+}
+
+fn decode_scalar_literal(
+    type_flags: ReflectTypeFlags,
+    scalar: ReflectNumericTraitsScalar,
+    words: &[u32],
+) -> ReflectSpecializationConstantValue {
+    let bits = match words {
+        [] => return ReflectSpecializationConstantValue::Undefined,
+        [low] => u64::from(*low),
+        [low, high, ..] => u64::from(*low) | (u64::from(*high) << 32),
+    };
+    let width = scalar.width;
+    if width == 0 || width > 64 {
+        return ReflectSpecializationConstantValue::Undefined;
+    }
+    if type_flags.contains(ReflectTypeFlags::INT) {
+        let shift = 64 - width;
+        if scalar.signedness != 0 {
+            ReflectSpecializationConstantValue::Int(((bits << shift) as i64) >> shift)
+        } else {
+            ReflectSpecializationConstantValue::UInt((bits << shift) >> shift)
+        }
+    } else if type_flags.contains(ReflectTypeFlags::FLOAT) {
+        match width {
+            16 => ReflectSpecializationConstantValue::Float(f64::from(half_to_f32(bits as u16))),
+            32 => ReflectSpecializationConstantValue::Float(f64::from(f32::from_bits(bits as u32))),
+            64 => ReflectSpecializationConstantValue::Float(f64::from_bits(bits)),
+            _ => ReflectSpecializationConstantValue::Undefined,
+        }
+    } else {
+        ReflectSpecializationConstantValue::Undefined
+    }
+}
+
+fn half_to_f32(half: u16) -> f32 {
+    let sign = u32::from(half & 0x8000) << 16;
+    let exponent = u32::from(half >> 10) & 0x1F;
+    let mantissa = u32::from(half) & 0x3FF;
+    let bits = match (exponent, mantissa) {
+        (0, 0) => sign,
+        (0, _) => {
+            // Subnormal half, renormalize into a normal single.
+            let mut exponent = 127 - 15 + 1;
+            let mut mantissa = mantissa;
+            while mantissa & 0x400 == 0 {
+                mantissa <<= 1;
+                exponent -= 1;
+            }
+            sign | (exponent << 23) | ((mantissa & 0x3FF) << 13)
+        }
+        (0x1F, _) => sign | 0x7F80_0000 | (mantissa << 13),
+        _ => sign | ((exponent + 127 - 15) << 23) | (mantissa << 13),
+    };
+    f32::from_bits(bits)
+}
+
+pub(crate) fn parse_execution_modes(code: &[u32]) -> HashMap<u32, Vec<ReflectExecutionMode>> {
+    let mut execution_modes: HashMap<u32, Vec<ReflectExecutionMode>> = HashMap::new();
+    for instruction in instructions(code) {
+        let operands = instruction.operands;
+        if (instruction.opcode == spirv::Op::ExecutionMode as u32
+            || instruction.opcode == spirv::Op::ExecutionModeId as u32)
+            && operands.len() >= 2
+        {
+            execution_modes
+                .entry(operands[0])
+                .or_default()
+                .push(decode_execution_mode(operands[1], &operands[2..]));
+        }
+    }
+    execution_modes
+}
+
+fn decode_execution_mode(mode: u32, operands: &[u32]) -> ReflectExecutionMode {
+    let size = |operands: &[u32]| ReflectEntryPointLocalSize {
+        x: operands[0],
+        y: operands[1],
+        z: operands[2],
+    };
+    let size_id = |operands: &[u32]| ReflectEntryPointLocalSizeId {
+        x_id: operands[0],
+        y_id: operands[1],
+        z_id: operands[2],
+    };
+    match (spirv::ExecutionMode::from_u32(mode), operands.len()) {
+        (Some(spirv::ExecutionMode::Invocations), 1) => {
+            ReflectExecutionMode::Invocations(operands[0])
+        }
+        (Some(spirv::ExecutionMode::SpacingEqual), 0) => ReflectExecutionMode::SpacingEqual,
+        (Some(spirv::ExecutionMode::SpacingFractionalEven), 0) => {
+            ReflectExecutionMode::SpacingFractionalEven
+        }
+        (Some(spirv::ExecutionMode::SpacingFractionalOdd), 0) => {
+            ReflectExecutionMode::SpacingFractionalOdd
+        }
+        (Some(spirv::ExecutionMode::VertexOrderCw), 0) => ReflectExecutionMode::VertexOrderCw,
+        (Some(spirv::ExecutionMode::VertexOrderCcw), 0) => ReflectExecutionMode::VertexOrderCcw,
+        (Some(spirv::ExecutionMode::PixelCenterInteger), 0) => {
+            ReflectExecutionMode::PixelCenterInteger
+        }
+        (Some(spirv::ExecutionMode::OriginUpperLeft), 0) => ReflectExecutionMode::OriginUpperLeft,
+        (Some(spirv::ExecutionMode::OriginLowerLeft), 0) => ReflectExecutionMode::OriginLowerLeft,
+        (Some(spirv::ExecutionMode::EarlyFragmentTests), 0) => {
+            ReflectExecutionMode::EarlyFragmentTests
+        }
+        (Some(spirv::ExecutionMode::PointMode), 0) => ReflectExecutionMode::PointMode,
+        (Some(spirv::ExecutionMode::Xfb), 0) => ReflectExecutionMode::Xfb,
+        (Some(spirv::ExecutionMode::DepthReplacing), 0) => ReflectExecutionMode::DepthReplacing,
+        (Some(spirv::ExecutionMode::DepthGreater), 0) => ReflectExecutionMode::DepthGreater,
+        (Some(spirv::ExecutionMode::DepthLess), 0) => ReflectExecutionMode::DepthLess,
+        (Some(spirv::ExecutionMode::DepthUnchanged), 0) => ReflectExecutionMode::DepthUnchanged,
+        (Some(spirv::ExecutionMode::LocalSize), 3) => {
+            ReflectExecutionMode::LocalSize(size(operands))
+        }
+        (Some(spirv::ExecutionMode::LocalSizeHint), 3) => {
+            ReflectExecutionMode::LocalSizeHint(size(operands))
+        }
+        (Some(spirv::ExecutionMode::InputPoints), 0) => ReflectExecutionMode::InputPoints,
+        (Some(spirv::ExecutionMode::InputLines), 0) => ReflectExecutionMode::InputLines,
+        (Some(spirv::ExecutionMode::InputLinesAdjacency), 0) => {
+            ReflectExecutionMode::InputLinesAdjacency
+        }
+        (Some(spirv::ExecutionMode::Triangles), 0) => ReflectExecutionMode::Triangles,
+        (Some(spirv::ExecutionMode::InputTrianglesAdjacency), 0) => {
+            ReflectExecutionMode::InputTrianglesAdjacency
+        }
+        (Some(spirv::ExecutionMode::Quads), 0) => ReflectExecutionMode::Quads,
+        (Some(spirv::ExecutionMode::Isolines), 0) => ReflectExecutionMode::Isolines,
+        (Some(spirv::ExecutionMode::OutputVertices), 1) => {
+            ReflectExecutionMode::OutputVertices(operands[0])
+        }
+        (Some(spirv::ExecutionMode::OutputPoints), 0) => ReflectExecutionMode::OutputPoints,
+        (Some(spirv::ExecutionMode::OutputLineStrip), 0) => ReflectExecutionMode::OutputLineStrip,
+        (Some(spirv::ExecutionMode::OutputTriangleStrip), 0) => {
+            ReflectExecutionMode::OutputTriangleStrip
+        }
+        (Some(spirv::ExecutionMode::VecTypeHint), 1) => {
+            ReflectExecutionMode::VecTypeHint(operands[0])
+        }
+        (Some(spirv::ExecutionMode::ContractionOff), 0) => ReflectExecutionMode::ContractionOff,
+        (Some(spirv::ExecutionMode::Initializer), 0) => ReflectExecutionMode::Initializer,
+        (Some(spirv::ExecutionMode::Finalizer), 0) => ReflectExecutionMode::Finalizer,
+        (Some(spirv::ExecutionMode::SubgroupSize), 1) => {
+            ReflectExecutionMode::SubgroupSize(operands[0])
+        }
+        (Some(spirv::ExecutionMode::SubgroupsPerWorkgroup), 1) => {
+            ReflectExecutionMode::SubgroupsPerWorkgroup(operands[0])
+        }
+        (Some(spirv::ExecutionMode::SubgroupsPerWorkgroupId), 1) => {
+            ReflectExecutionMode::SubgroupsPerWorkgroupId(operands[0])
+        }
+        (Some(spirv::ExecutionMode::LocalSizeId), 3) => {
+            ReflectExecutionMode::LocalSizeId(size_id(operands))
+        }
+        (Some(spirv::ExecutionMode::LocalSizeHintId), 3) => {
+            ReflectExecutionMode::LocalSizeHintId(size_id(operands))
+        }
+        (Some(spirv::ExecutionMode::PostDepthCoverage), 0) => {
+            ReflectExecutionMode::PostDepthCoverage
+        }
+        (Some(spirv::ExecutionMode::DenormPreserve), 1) => {
+            ReflectExecutionMode::DenormPreserve(operands[0])
+        }
+        (Some(spirv::ExecutionMode::DenormFlushToZero), 1) => {
+            ReflectExecutionMode::DenormFlushToZero(operands[0])
+        }
+        (Some(spirv::ExecutionMode::SignedZeroInfNanPreserve), 1) => {
+            ReflectExecutionMode::SignedZeroInfNanPreserve(operands[0])
+        }
+        (Some(spirv::ExecutionMode::RoundingModeRTE), 1) => {
+            ReflectExecutionMode::RoundingModeRTE(operands[0])
+        }
+        (Some(spirv::ExecutionMode::RoundingModeRTZ), 1) => {
+            ReflectExecutionMode::RoundingModeRTZ(operands[0])
+        }
+        (Some(spirv::ExecutionMode::StencilRefReplacingEXT), 0) => {
+            ReflectExecutionMode::StencilRefReplacingEXT
+        }
+        (Some(spirv::ExecutionMode::OutputLinesNV), 0) => ReflectExecutionMode::OutputLinesNV,
+        (Some(spirv::ExecutionMode::OutputPrimitivesNV), 1) => {
+            ReflectExecutionMode::OutputPrimitivesNV(operands[0])
+        }
+        (Some(spirv::ExecutionMode::DerivativeGroupQuadsNV), 0) => {
+            ReflectExecutionMode::DerivativeGroupQuadsNV
+        }
+        (Some(spirv::ExecutionMode::DerivativeGroupLinearNV), 0) => {
+            ReflectExecutionMode::DerivativeGroupLinearNV
+        }
+        (Some(spirv::ExecutionMode::OutputTrianglesNV), 0) => {
+            ReflectExecutionMode::OutputTrianglesNV
+        }
+        (Some(spirv::ExecutionMode::PixelInterlockOrderedEXT), 0) => {
+            ReflectExecutionMode::PixelInterlockOrderedEXT
+        }
+        (Some(spirv::ExecutionMode::PixelInterlockUnorderedEXT), 0) => {
+            ReflectExecutionMode::PixelInterlockUnorderedEXT
+        }
+        (Some(spirv::ExecutionMode::SampleInterlockOrderedEXT), 0) => {
+            ReflectExecutionMode::SampleInterlockOrderedEXT
+        }
+        (Some(spirv::ExecutionMode::SampleInterlockUnorderedEXT), 0) => {
+            ReflectExecutionMode::SampleInterlockUnorderedEXT
+        }
+        (Some(spirv::ExecutionMode::ShadingRateInterlockOrderedEXT), 0) => {
+            ReflectExecutionMode::ShadingRateInterlockOrderedEXT
+        }
+        (Some(spirv::ExecutionMode::ShadingRateInterlockUnorderedEXT), 0) => {
+            ReflectExecutionMode::ShadingRateInterlockUnorderedEXT
+        }
+        (Some(spirv::ExecutionMode::MaxWorkgroupSizeINTEL), 3) => {
+            ReflectExecutionMode::MaxWorkgroupSizeINTEL(size(operands))
+        }
+        (Some(spirv::ExecutionMode::MaxWorkDimINTEL), 1) => {
+            ReflectExecutionMode::MaxWorkDimINTEL(operands[0])
+        }
+        (Some(spirv::ExecutionMode::NoGlobalOffsetINTEL), 0) => {
+            ReflectExecutionMode::NoGlobalOffsetINTEL
+        }
+        (Some(spirv::ExecutionMode::NumSIMDWorkitemsINTEL), 1) => {
+            ReflectExecutionMode::NumSIMDWorkitemsINTEL(operands[0])
+        }
+        _ => ReflectExecutionMode::Unknown {
+            mode,
+            operands: operands.to_vec(),
+        },
+    }
+}
@@ -0,0 +1,212 @@
+use crate::error::ReflectError;
+use crate::types::{
+    ReflectBlockVariable, ReflectDescriptorBinding, ReflectDescriptorType, ReflectShaderStageFlags,
+};
+use crate::ReflectModule;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PipelineLayoutBinding {
+    pub binding: ReflectDescriptorBinding,
+    pub stage_flags: ReflectShaderStageFlags,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PipelineLayoutSet {
+    pub set: u32,
+    pub bindings: Vec<PipelineLayoutBinding>,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PipelineLayoutPushConstantRange {
+    pub stage_flags: ReflectShaderStageFlags,
+    pub offset: u32,
+    pub size: u32,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PipelineLayout {
+    pub sets: Vec<PipelineLayoutSet>,
+    pub push_constant_ranges: Vec<PipelineLayoutPushConstantRange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum PipelineLayoutConflict {
+    DescriptorType {
+        set: u32,
+        binding: u32,
+        stage_flags: ReflectShaderStageFlags,
+        existing: ReflectDescriptorType,
+        conflicting: ReflectDescriptorType,
+    },
+    Count {
+        set: u32,
+        binding: u32,
+        stage_flags: ReflectShaderStageFlags,
+        existing: u32,
+        conflicting: u32,
+    },
+    BlockSize {
+        set: u32,
+        binding: u32,
+        stage_flags: ReflectShaderStageFlags,
+        existing: u32,
+        conflicting: u32,
+    },
+    PushConstantRangeOverflow {
+        stage_flags: ReflectShaderStageFlags,
+        offset: u32,
+        size: u32,
+    },
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct PipelineLayoutBuilder {
+    sets: BTreeMap<u32, BTreeMap<u32, PipelineLayoutBinding>>,
+    push_constant_ranges: BTreeMap<u32, (u32, u32)>,
+    conflicts: Vec<PipelineLayoutConflict>,
+}
+
+impl PipelineLayoutBuilder {
+    pub fn new() -> Self {
+        PipelineLayoutBuilder::default()
+    }
+
+    pub fn add_module(&mut self, module: &impl ReflectModule) -> Result<&mut Self, ReflectError> {
+        for entry_point in module.enumerate_entry_points()? {
+            let stage_flags = entry_point.shader_stage;
+            for set in module.enumerate_descriptor_sets(Some(&entry_point.name))? {
+                for binding in set.bindings {
+                    self.add_binding(binding, stage_flags);
+                }
+            }
+            for block in module.enumerate_push_constant_blocks(Some(&entry_point.name))? {
+                self.add_push_constant_block(&block, stage_flags);
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn add_binding(
+        &mut self,
+        binding: ReflectDescriptorBinding,
+        stage_flags: ReflectShaderStageFlags,
+    ) -> &mut Self {
+        let set = binding.set;
+        let bindings = self.sets.entry(set).or_default();
+        match bindings.get_mut(&binding.binding) {
+            Some(existing) => {
+                let conflict = if existing.binding.descriptor_type != binding.descriptor_type {
+                    Some(PipelineLayoutConflict::DescriptorType {
+                        set,
+                        binding: binding.binding,
+                        stage_flags,
+                        existing: existing.binding.descriptor_type,
+                        conflicting: binding.descriptor_type,
+                    })
+                } else if existing.binding.count != binding.count {
+                    Some(PipelineLayoutConflict::Count {
+                        set,
+                        binding: binding.binding,
+                        stage_flags,
+                        existing: existing.binding.count,
+                        conflicting: binding.count,
+                    })
+                } else if existing.binding.block.size != binding.block.size {
+                    Some(PipelineLayoutConflict::BlockSize {
+                        set,
+                        binding: binding.binding,
+                        stage_flags,
+                        existing: existing.binding.block.size,
+                        conflicting: binding.block.size,
+                    })
+                } else {
+                    None
+                };
+                match conflict {
+                    Some(conflict) => self.conflicts.push(conflict),
+                    None => existing.stage_flags |= stage_flags,
+                }
+            }
+            None => {
+                bindings.insert(
+                    binding.binding,
+                    PipelineLayoutBinding {
+                        binding,
+                        stage_flags,
+                    },
+                );
+            }
+        }
+        self
+    }
+
+    pub fn add_push_constant_block(
+        &mut self,
+        block: &ReflectBlockVariable,
+        stage_flags: ReflectShaderStageFlags,
+    ) -> &mut Self {
+        let end = match block.offset.checked_add(block.size) {
+            Some(end) => end,
+            None => {
+                self.conflicts
+                    .push(PipelineLayoutConflict::PushConstantRangeOverflow {
+                        stage_flags,
+                        offset: block.offset,
+                        size: block.size,
+                    });
+                return self;
+            }
+        };
+        self.push_constant_ranges
+            .entry(stage_flags.bits())
+            .and_modify(|range| {
+                range.0 = range.0.min(block.offset);
+                range.1 = range.1.max(end);
+            })
+            .or_insert((block.offset, end));
+        self
+    }
+
+    pub fn conflicts(&self) -> &[PipelineLayoutConflict] {
+        &self.conflicts
+    }
+
+    pub fn build(&self) -> Result<PipelineLayout, Vec<PipelineLayoutConflict>> {
+        if !self.conflicts.is_empty() {
+            return Err(self.conflicts.clone());
+        }
+
+        let sets = self
+            .sets
+            .iter()
+            .map(|(&set, bindings)| PipelineLayoutSet {
+                set,
+                bindings: bindings.values().cloned().collect(),
+            })
+            .collect();
+
+        // Stages that use the same push constant range share a single range.
+        let mut push_constant_ranges: Vec<PipelineLayoutPushConstantRange> = Vec::new();
+        for (&stage_bits, &(offset, end)) in &self.push_constant_ranges {
+            let stage_flags = ReflectShaderStageFlags::from_bits_truncate(stage_bits);
+            let size = end - offset;
+            match push_constant_ranges
+                .iter_mut()
+                .find(|range| range.offset == offset && range.size == size)
+            {
+                Some(range) => range.stage_flags |= stage_flags,
+                None => push_constant_ranges.push(PipelineLayoutPushConstantRange {
+                    stage_flags,
+                    offset,
+                    size,
+                }),
+            }
+        }
+
+        Ok(PipelineLayout {
+            sets,
+            push_constant_ranges,
+        })
+    }
+}
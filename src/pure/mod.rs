@@ -0,0 +1,507 @@
+mod parse;
+mod reflect;
+
+use crate::error::{ReflectError, ReflectErrorKind};
+use crate::types;
+use num_traits::cast::FromPrimitive;
+
+#[derive(Default, Clone)]
+pub struct ShaderModule {
+    module: Option<reflect::Module>,
+}
+
+impl ShaderModule {
+    pub fn load_u8_data(spv_data: &[u8]) -> Result<ShaderModule, ReflectError> {
+        create_shader_module(spv_data)
+    }
+
+    pub fn load_u32_data(spv_data: &[u32]) -> Result<ShaderModule, ReflectError> {
+        Ok(ShaderModule {
+            module: Some(reflect::Module::new(spv_data.to_vec())?),
+        })
+    }
+
+    // Matches the C backend, whose modules can only be copied by re-parsing their code.
+    pub fn try_clone(&self) -> Result<ShaderModule, ReflectError> {
+        Ok(self.clone())
+    }
+
+    pub fn get_code(&self) -> Vec<u32> {
+        match self.module {
+            Some(ref module) => module.code.clone(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn get_generator(&self) -> types::ReflectGenerator {
+        match self.module {
+            Some(ref module) => module.generator,
+            None => types::ReflectGenerator::Unknown,
+        }
+    }
+
+    pub fn enumerate_capabilities(&self) -> Result<Vec<types::ReflectCapability>, ReflectError> {
+        match self.module {
+            Some(ref module) => Ok(module.capabilities.clone()),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn get_shader_stage(&self) -> types::ReflectShaderStageFlags {
+        match self
+            .module
+            .as_ref()
+            .and_then(|module| module.entry_points.first())
+        {
+            Some(entry_point) => entry_point.shader_stage,
+            None => types::ReflectShaderStageFlags::UNDEFINED,
+        }
+    }
+
+    pub fn get_source_language(&self) -> spirv::SourceLanguage {
+        match self.module {
+            Some(ref module) => match spirv::SourceLanguage::from_u32(module.source_language) {
+                Some(language) => language,
+                None => spirv::SourceLanguage::Unknown,
+            },
+            None => spirv::SourceLanguage::Unknown,
+        }
+    }
+
+    pub fn get_source_language_version(&self) -> u32 {
+        match self.module {
+            Some(ref module) => module.source_language_version,
+            None => 0,
+        }
+    }
+
+    pub fn get_source_file(&self) -> String {
+        match self.module {
+            Some(ref module) => module.source_file.clone(),
+            None => String::new(),
+        }
+    }
+
+    pub fn get_source_text(&self) -> String {
+        match self.module {
+            Some(ref module) => module.source_text.clone(),
+            None => String::new(),
+        }
+    }
+
+    pub fn get_spirv_execution_model(&self) -> spirv::ExecutionModel {
+        match self
+            .module
+            .as_ref()
+            .and_then(|module| module.entry_points.first())
+        {
+            Some(entry_point) => entry_point.spirv_execution_model,
+            None => spirv::ExecutionModel::Vertex,
+        }
+    }
+
+    pub fn enumerate_input_variables(
+        &self,
+        entry_point: Option<&str>,
+    ) -> Result<Vec<types::ReflectInterfaceVariable>, ReflectError> {
+        match self.module {
+            Some(ref module) => match entry_point {
+                Some(entry_point) => Ok(find_entry_point(module, entry_point)?
+                    .input_variables
+                    .clone()),
+                None => Ok(module.input_variables().to_vec()),
+            },
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn enumerate_output_variables(
+        &self,
+        entry_point: Option<&str>,
+    ) -> Result<Vec<types::ReflectInterfaceVariable>, ReflectError> {
+        match self.module {
+            Some(ref module) => match entry_point {
+                Some(entry_point) => Ok(find_entry_point(module, entry_point)?
+                    .output_variables
+                    .clone()),
+                None => Ok(module.output_variables().to_vec()),
+            },
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn enumerate_interface_variables(
+        &self,
+        entry_point: Option<&str>,
+    ) -> Result<Vec<types::ReflectInterfaceVariable>, ReflectError> {
+        let module = match self.module {
+            Some(ref module) => module,
+            None => return Ok(Vec::new()),
+        };
+        // Like SPIRV-Reflect, inputs and outputs keep the order the entry point declares them
+        // in. Without an entry point, the interfaces of all entry points are searched in turn.
+        let interface: Vec<u32> = match entry_point {
+            Some(name) => module
+                .entry_points
+                .iter()
+                .position(|entry_point| entry_point.name == name)
+                .and_then(|index| module.interfaces.get(index))
+                .ok_or_else(|| element_not_found().with_entry_point(Some(name)))?
+                .clone(),
+            None => module.interfaces.concat(),
+        };
+        let mut variables = self.enumerate_input_variables(entry_point)?;
+        variables.extend(self.enumerate_output_variables(entry_point)?);
+        variables.sort_by_key(|variable| {
+            interface
+                .iter()
+                .position(|&id| id == variable.spirv_id)
+                .unwrap_or(usize::MAX)
+        });
+        Ok(variables)
+    }
+
+    pub fn get_input_variable_by_location(
+        &self,
+        entry_point: Option<&str>,
+        location: u32,
+    ) -> Result<types::ReflectInterfaceVariable, ReflectError> {
+        let variables = self.enumerate_input_variables(entry_point)?;
+        find_variable_by_location(variables, location, entry_point)
+    }
+
+    pub fn get_output_variable_by_location(
+        &self,
+        entry_point: Option<&str>,
+        location: u32,
+    ) -> Result<types::ReflectInterfaceVariable, ReflectError> {
+        let variables = self.enumerate_output_variables(entry_point)?;
+        find_variable_by_location(variables, location, entry_point)
+    }
+
+    pub fn get_input_variable_by_semantic(
+        &self,
+        entry_point: Option<&str>,
+        semantic: &str,
+    ) -> Result<types::ReflectInterfaceVariable, ReflectError> {
+        let variables = self.enumerate_input_variables(entry_point)?;
+        find_variable_by_semantic(variables, semantic, entry_point)
+    }
+
+    pub fn get_output_variable_by_semantic(
+        &self,
+        entry_point: Option<&str>,
+        semantic: &str,
+    ) -> Result<types::ReflectInterfaceVariable, ReflectError> {
+        let variables = self.enumerate_output_variables(entry_point)?;
+        find_variable_by_semantic(variables, semantic, entry_point)
+    }
+
+    pub fn enumerate_descriptor_bindings(
+        &self,
+        entry_point: Option<&str>,
+    ) -> Result<Vec<types::ReflectDescriptorBinding>, ReflectError> {
+        match self.module {
+            Some(ref module) => match entry_point {
+                Some(entry_point) => {
+                    let entry_point = find_entry_point(module, entry_point)?;
+                    Ok(module
+                        .descriptor_bindings
+                        .iter()
+                        .filter(|binding| {
+                            entry_point
+                                .used_uniforms
+                                .binary_search(&binding.spirv_id)
+                                .is_ok()
+                        })
+                        .cloned()
+                        .collect())
+                }
+                None => Ok(module.descriptor_bindings.clone()),
+            },
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn enumerate_descriptor_sets(
+        &self,
+        entry_point: Option<&str>,
+    ) -> Result<Vec<types::ReflectDescriptorSet>, ReflectError> {
+        match self.module {
+            Some(ref module) => match entry_point {
+                Some(entry_point) => Ok(find_entry_point(module, entry_point)?
+                    .descriptor_sets
+                    .clone()),
+                None => Ok(module.descriptor_sets.clone()),
+            },
+            None => Ok(Vec::new()),
+        }
+    }
+
+    // Only the bindings an entry point statically uses. Without an entry point, that's any
+    // entry point of the module.
+    pub fn enumerate_accessed_descriptor_bindings(
+        &self,
+        entry_point: Option<&str>,
+    ) -> Result<Vec<types::ReflectDescriptorBinding>, ReflectError> {
+        let mut bindings = self.enumerate_descriptor_bindings(entry_point)?;
+        bindings.retain(|binding| binding.accessed);
+        Ok(bindings)
+    }
+
+    pub fn enumerate_accessed_descriptor_sets(
+        &self,
+        entry_point: Option<&str>,
+    ) -> Result<Vec<types::ReflectDescriptorSet>, ReflectError> {
+        let mut sets = self.enumerate_descriptor_sets(entry_point)?;
+        for set in &mut sets {
+            set.bindings.retain(|binding| binding.accessed);
+        }
+        sets.retain(|set| !set.bindings.is_empty());
+        Ok(sets)
+    }
+
+    pub fn enumerate_push_constant_blocks(
+        &self,
+        entry_point: Option<&str>,
+    ) -> Result<Vec<types::ReflectBlockVariable>, ReflectError> {
+        match self.module {
+            Some(ref module) => match entry_point {
+                Some(entry_point) => {
+                    let entry_point = find_entry_point(module, entry_point)?;
+                    Ok(module
+                        .push_constant_blocks
+                        .iter()
+                        .filter(|block| {
+                            entry_point
+                                .used_push_constants
+                                .binary_search(&block.spirv_id)
+                                .is_ok()
+                        })
+                        .cloned()
+                        .collect())
+                }
+                None => Ok(module.push_constant_blocks.clone()),
+            },
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn enumerate_specialization_constants(
+        &self,
+    ) -> Result<Vec<types::ReflectSpecializationConstant>, ReflectError> {
+        match self.module {
+            Some(ref module) => Ok(module.specialization_constants.clone()),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn enumerate_entry_points(&self) -> Result<Vec<types::ReflectEntryPoint>, ReflectError> {
+        match self.module {
+            Some(ref module) => Ok(module.entry_points.clone()),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn get_entry_point_name(&self) -> String {
+        match self
+            .module
+            .as_ref()
+            .and_then(|module| module.entry_points.first())
+        {
+            Some(entry_point) => entry_point.name.clone(),
+            None => String::new(),
+        }
+    }
+
+    pub fn change_descriptor_binding_numbers(
+        &mut self,
+        binding: &types::descriptor::ReflectDescriptorBinding,
+        new_binding: u32,
+        new_set: Option<u32>,
+    ) -> Result<(), ReflectError> {
+        match self.module {
+            Some(ref mut module) => {
+                let (binding_word_offset, set_word_offset) =
+                    match module.descriptor_bindings.iter().find(|module_binding| {
+                        module_binding.spirv_id == binding.spirv_id
+                            && module_binding.binding == binding.binding
+                            && module_binding.set == binding.set
+                    }) {
+                        Some(module_binding) => module_binding.word_offset,
+                        None => {
+                            return Err(element_not_found()
+                                .with_binding(binding.set, binding.binding)
+                                .with_word_offset(binding.word_offset.0))
+                        }
+                    };
+                let mut words = vec![(binding_word_offset, new_binding)];
+                if let Some(new_set) = new_set {
+                    words.push((set_word_offset, new_set));
+                }
+                module.patch(&words).map_err(|err| {
+                    err.with_binding(binding.set, binding.binding)
+                        .with_word_offset(binding_word_offset)
+                })
+            }
+            None => Ok(()),
+        }
+    }
+
+    // Patches all bindings before reflecting the module again, rather than once per binding.
+    pub(crate) fn patch_descriptor_bindings(
+        &mut self,
+        changes: &[(&types::ReflectDescriptorBinding, u32, u32)],
+    ) -> Result<(), ReflectError> {
+        let module = match self.module {
+            Some(ref mut module) => module,
+            None => return Ok(()),
+        };
+        let mut words = Vec::with_capacity(changes.len() * 2);
+        for &(binding, new_binding, new_set) in changes {
+            let (binding_word_offset, set_word_offset) =
+                match module.descriptor_bindings.iter().find(|module_binding| {
+                    module_binding.spirv_id == binding.spirv_id
+                        && module_binding.binding == binding.binding
+                        && module_binding.set == binding.set
+                }) {
+                    Some(module_binding) => module_binding.word_offset,
+                    None => {
+                        return Err(element_not_found()
+                            .with_binding(binding.set, binding.binding)
+                            .with_word_offset(binding.word_offset.0))
+                    }
+                };
+            words.push((binding_word_offset, new_binding));
+            words.push((set_word_offset, new_set));
+        }
+        module.patch(&words)
+    }
+
+    pub fn change_descriptor_set_number(
+        &mut self,
+        set: &types::descriptor::ReflectDescriptorSet,
+        new_set: u32,
+    ) -> Result<(), ReflectError> {
+        match self.module {
+            Some(ref mut module) => {
+                let words: Vec<(u32, u32)> =
+                    match module.descriptor_sets.iter().find(|module_set| {
+                        module_set.set == set.set
+                            && module_set.bindings.len() == set.bindings.len()
+                            && module_set.bindings.iter().zip(&set.bindings).all(
+                                |(module_binding, binding)| {
+                                    module_binding.spirv_id == binding.spirv_id
+                                },
+                            )
+                    }) {
+                        Some(module_set) => module_set
+                            .bindings
+                            .iter()
+                            .map(|binding| (binding.word_offset.1, new_set))
+                            .collect(),
+                        None => return Err(element_not_found().with_set(set.set)),
+                    };
+                module.patch(&words).map_err(|err| err.with_set(set.set))
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn change_input_variable_location(
+        &mut self,
+        variable: &types::variable::ReflectInterfaceVariable,
+        new_location: u32,
+    ) -> Result<(), ReflectError> {
+        match self.module {
+            Some(ref mut module) => {
+                let word_offset = find_location_word_offset(module.input_variables(), variable)?;
+                module
+                    .patch(&[(word_offset, new_location)])
+                    .map_err(|err| err.with_word_offset(word_offset))
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn change_output_variable_location(
+        &mut self,
+        variable: &types::variable::ReflectInterfaceVariable,
+        new_location: u32,
+    ) -> Result<(), ReflectError> {
+        match self.module {
+            Some(ref mut module) => {
+                let word_offset = find_location_word_offset(module.output_variables(), variable)?;
+                module
+                    .patch(&[(word_offset, new_location)])
+                    .map_err(|err| err.with_word_offset(word_offset))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+fn element_not_found() -> ReflectError {
+    ReflectError::new(ReflectErrorKind::ElementNotFound)
+}
+
+fn find_entry_point<'a>(
+    module: &'a reflect::Module,
+    name: &str,
+) -> Result<&'a types::ReflectEntryPoint, ReflectError> {
+    module
+        .entry_points
+        .iter()
+        .find(|entry_point| entry_point.name == name)
+        .ok_or_else(|| element_not_found().with_entry_point(Some(name)))
+}
+
+// Like SPIRV-Reflect, only top-level variables are searched and undecorated ones never match.
+fn find_variable_by_location(
+    variables: Vec<types::ReflectInterfaceVariable>,
+    location: u32,
+    entry_point: Option<&str>,
+) -> Result<types::ReflectInterfaceVariable, ReflectError> {
+    variables
+        .into_iter()
+        .find(|variable| location != u32::MAX && variable.location == location)
+        .ok_or_else(|| element_not_found().with_entry_point(entry_point))
+}
+
+fn find_variable_by_semantic(
+    variables: Vec<types::ReflectInterfaceVariable>,
+    semantic: &str,
+    entry_point: Option<&str>,
+) -> Result<types::ReflectInterfaceVariable, ReflectError> {
+    variables
+        .into_iter()
+        .find(|variable| !semantic.is_empty() && variable.semantic == semantic)
+        .ok_or_else(|| element_not_found().with_entry_point(entry_point))
+}
+
+// Built-ins have no Location decoration, so there is no word to patch.
+fn find_location_word_offset(
+    variables: &[types::ReflectInterfaceVariable],
+    variable: &types::ReflectInterfaceVariable,
+) -> Result<u32, ReflectError> {
+    variables
+        .iter()
+        .find(|module_variable| {
+            module_variable.spirv_id == variable.spirv_id
+                && module_variable.location == variable.location
+                && module_variable.word_offset != 0
+        })
+        .map(|module_variable| module_variable.word_offset)
+        .ok_or_else(|| element_not_found().with_word_offset(variable.word_offset))
+}
+
+pub fn create_shader_module(spv_data: &[u8]) -> Result<ShaderModule, ReflectError> {
+    let words = spv_data.chunks_exact(4);
+    if !words.remainder().is_empty() {
+        return Err(ReflectError::new(ReflectErrorKind::SpirvInvalidCodeSize));
+    }
+    let code: Vec<u32> = words
+        .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+        .collect();
+    ShaderModule::load_u32_data(&code)
+}
@@ -0,0 +1,433 @@
+use crate::error::{ReflectError, ReflectErrorKind};
+use crate::parser::{SPIRV_HEADER_WORD_COUNT, SPIRV_MAGIC_NUMBER};
+use crate::types::{ReflectDecorationFlags, ReflectUserType};
+use num_traits::cast::FromPrimitive;
+use spirv::{Decoration, Op};
+use std::collections::HashMap;
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Node<'a> {
+    pub(crate) opcode: u32,
+    pub(crate) word_offset: u32,
+    pub(crate) operands: &'a [u32],
+}
+
+impl<'a> Node<'a> {
+    pub(crate) fn op(&self) -> Option<Op> {
+        Op::from_u32(self.opcode)
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct Literal {
+    pub(crate) value: u32,
+    pub(crate) word_offset: u32,
+}
+
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Decorations {
+    pub(crate) flags: ReflectDecorationFlags,
+    pub(crate) set: Option<Literal>,
+    pub(crate) binding: Option<Literal>,
+    pub(crate) location: Option<Literal>,
+    pub(crate) component: Option<u32>,
+    pub(crate) input_attachment_index: Option<u32>,
+    pub(crate) offset: u32,
+    pub(crate) array_stride: u32,
+    pub(crate) matrix_stride: u32,
+    pub(crate) spec_id: Option<u32>,
+    pub(crate) built_in: Option<u32>,
+    pub(crate) semantic: String,
+    pub(crate) counter_buffer: Option<u32>,
+    pub(crate) user_type: ReflectUserType,
+}
+
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Function {
+    pub(crate) callees: Vec<u32>,
+    pub(crate) accessed: Vec<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct EntryPoint {
+    pub(crate) execution_model: u32,
+    pub(crate) function_id: u32,
+    pub(crate) name: String,
+    pub(crate) interface: Vec<u32>,
+}
+
+// Instructions of a module indexed by result id, with the debug names, decorations and
+// function bodies resolved up front.
+pub(crate) struct Parser<'a> {
+    pub(crate) nodes: Vec<Node<'a>>,
+    pub(crate) entry_points: Vec<EntryPoint>,
+    pub(crate) functions: HashMap<u32, Function>,
+    ids: HashMap<u32, usize>,
+    strings: HashMap<u32, String>,
+    names: HashMap<u32, String>,
+    member_names: HashMap<(u32, u32), String>,
+    decorations: HashMap<u32, Decorations>,
+    member_decorations: HashMap<(u32, u32), Decorations>,
+    no_decorations: Decorations,
+}
+
+impl<'a> Parser<'a> {
+    pub(crate) fn new(code: &'a [u32]) -> Result<Self, ReflectError> {
+        if code.len() < SPIRV_HEADER_WORD_COUNT {
+            return Err(ReflectError::new(ReflectErrorKind::SpirvInvalidCodeSize));
+        }
+        if code[0] != SPIRV_MAGIC_NUMBER {
+            return Err(ReflectError::new(ReflectErrorKind::SpirvInvalidMagicNumber));
+        }
+
+        let mut parser = Parser {
+            nodes: Vec::new(),
+            entry_points: Vec::new(),
+            functions: HashMap::new(),
+            ids: HashMap::new(),
+            strings: HashMap::new(),
+            names: HashMap::new(),
+            member_names: HashMap::new(),
+            decorations: HashMap::new(),
+            member_decorations: HashMap::new(),
+            no_decorations: Decorations::default(),
+        };
+        let mut function: Option<(u32, Function)> = None;
+        let mut offset = SPIRV_HEADER_WORD_COUNT;
+        while offset < code.len() {
+            let word_count = (code[offset] >> 16) as usize;
+            if word_count == 0 || offset + word_count > code.len() {
+                return Err(ReflectError::new(ReflectErrorKind::SpirvUnexpectedEof)
+                    .with_word_offset(offset as u32));
+            }
+            let node = Node {
+                opcode: code[offset] & 0xFFFF,
+                word_offset: offset as u32,
+                operands: &code[offset + 1..offset + word_count],
+            };
+            parser.parse_node(node, &mut function)?;
+            offset += word_count;
+        }
+        Ok(parser)
+    }
+
+    fn parse_node(
+        &mut self,
+        node: Node<'a>,
+        function: &mut Option<(u32, Function)>,
+    ) -> Result<(), ReflectError> {
+        let operands = node.operands;
+        let invalid_instruction = || {
+            ReflectError::new(ReflectErrorKind::SpirvInvalidInstruction)
+                .with_word_offset(node.word_offset)
+        };
+        let op = match node.op() {
+            Some(op) => op,
+            None => {
+                self.nodes.push(node);
+                return Ok(());
+            }
+        };
+        if operands.len() < min_operand_count(op) {
+            return Err(invalid_instruction());
+        }
+
+        match op {
+            Op::String => {
+                self.strings
+                    .insert(operands[0], decode_string(&operands[1..]));
+            }
+            Op::Name => {
+                self.names
+                    .insert(operands[0], decode_string(&operands[1..]));
+            }
+            Op::MemberName => {
+                self.member_names
+                    .insert((operands[0], operands[1]), decode_string(&operands[2..]));
+            }
+            Op::EntryPoint => {
+                let name_word_count = string_word_count(&operands[2..]);
+                self.entry_points.push(EntryPoint {
+                    execution_model: operands[0],
+                    function_id: operands[1],
+                    name: decode_string(&operands[2..]),
+                    interface: operands[2 + name_word_count..].to_vec(),
+                });
+            }
+            Op::Decorate | Op::DecorateId | Op::DecorateString => {
+                let decorations = self.decorations.entry(operands[0]).or_default();
+                apply_decoration(decorations, &operands[1..], node.word_offset + 3)
+                    .ok_or_else(invalid_instruction)?;
+            }
+            Op::MemberDecorate | Op::MemberDecorateString => {
+                let decorations = self
+                    .member_decorations
+                    .entry((operands[0], operands[1]))
+                    .or_default();
+                apply_decoration(decorations, &operands[2..], node.word_offset + 4)
+                    .ok_or_else(invalid_instruction)?;
+            }
+            Op::Function => {
+                *function = Some((operands[1], Function::default()));
+            }
+            Op::FunctionEnd => {
+                if let Some((id, function)) = function.take() {
+                    self.functions.insert(id, function);
+                }
+            }
+            Op::FunctionCall => {
+                if let Some((_, ref mut function)) = function {
+                    function.callees.push(operands[2]);
+                    function.accessed.extend_from_slice(&operands[3..]);
+                }
+            }
+            Op::Load
+            | Op::AccessChain
+            | Op::InBoundsAccessChain
+            | Op::PtrAccessChain
+            | Op::InBoundsPtrAccessChain
+            | Op::ArrayLength
+            | Op::GenericPtrMemSemantics
+            | Op::ImageTexelPointer => {
+                if let Some((_, ref mut function)) = function {
+                    function.accessed.push(operands[2]);
+                }
+            }
+            Op::Store => {
+                if let Some((_, ref mut function)) = function {
+                    function.accessed.push(operands[0]);
+                }
+            }
+            Op::CopyMemory | Op::CopyMemorySized => {
+                if let Some((_, ref mut function)) = function {
+                    function.accessed.extend_from_slice(&operands[..2]);
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(id) = result_id(op, operands) {
+            self.ids.insert(id, self.nodes.len());
+        }
+        self.nodes.push(node);
+        Ok(())
+    }
+
+    pub(crate) fn node(&self, id: u32) -> Result<&Node<'a>, ReflectError> {
+        self.ids
+            .get(&id)
+            .map(|&index| &self.nodes[index])
+            .ok_or_else(|| ReflectError::new(ReflectErrorKind::SpirvInvalidIdReference))
+    }
+
+    pub(crate) fn string(&self, id: u32) -> String {
+        self.strings.get(&id).cloned().unwrap_or_default()
+    }
+
+    pub(crate) fn name(&self, id: u32) -> String {
+        self.names.get(&id).cloned().unwrap_or_default()
+    }
+
+    pub(crate) fn member_name(&self, id: u32, member: u32) -> String {
+        self.member_names
+            .get(&(id, member))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn decorations(&self, id: u32) -> &Decorations {
+        self.decorations.get(&id).unwrap_or(&self.no_decorations)
+    }
+
+    pub(crate) fn member_decorations(&self, id: u32, member: u32) -> &Decorations {
+        self.member_decorations
+            .get(&(id, member))
+            .unwrap_or(&self.no_decorations)
+    }
+}
+
+fn min_operand_count(op: Op) -> usize {
+    match op {
+        Op::Capability | Op::String | Op::Name | Op::TypeStruct => 1,
+        Op::MemberName | Op::EntryPoint | Op::Source | Op::Decorate | Op::DecorateId => 2,
+        Op::DecorateString | Op::TypeFloat | Op::TypeSampledImage | Op::TypeRuntimeArray => 2,
+        Op::Store | Op::CopyMemory => 2,
+        Op::MemberDecorate | Op::MemberDecorateString | Op::TypeInt | Op::TypeVector => 3,
+        Op::TypeMatrix | Op::TypeArray | Op::TypePointer | Op::Constant | Op::Variable => 3,
+        Op::Load | Op::AccessChain | Op::InBoundsAccessChain | Op::FunctionCall => 3,
+        Op::PtrAccessChain | Op::InBoundsPtrAccessChain | Op::CopyMemorySized => 3,
+        Op::GenericPtrMemSemantics | Op::SpecConstant => 3,
+        Op::SpecConstantTrue | Op::SpecConstantFalse => 2,
+        Op::Function | Op::ArrayLength => 4,
+        Op::ImageTexelPointer => 5,
+        Op::TypeImage => 8,
+        _ => 0,
+    }
+}
+
+fn result_id(op: Op, operands: &[u32]) -> Option<u32> {
+    match op {
+        Op::String
+        | Op::TypeVoid
+        | Op::TypeBool
+        | Op::TypeInt
+        | Op::TypeFloat
+        | Op::TypeVector
+        | Op::TypeMatrix
+        | Op::TypeImage
+        | Op::TypeSampler
+        | Op::TypeSampledImage
+        | Op::TypeArray
+        | Op::TypeRuntimeArray
+        | Op::TypeStruct
+        | Op::TypeOpaque
+        | Op::TypePointer
+        | Op::TypeFunction
+        | Op::TypeAccelerationStructureNV
+        | Op::TypeRayQueryKHR => operands.first().copied(),
+        Op::ConstantTrue
+        | Op::ConstantFalse
+        | Op::Constant
+        | Op::ConstantComposite
+        | Op::ConstantNull
+        | Op::SpecConstantTrue
+        | Op::SpecConstantFalse
+        | Op::SpecConstant
+        | Op::SpecConstantComposite
+        | Op::SpecConstantOp
+        | Op::Variable
+        | Op::Function => operands.get(1).copied(),
+        // Address arithmetic, to find the constant offsets of ByteAddressBuffer accesses.
+        Op::IAdd | Op::IMul | Op::ShiftRightLogical | Op::ShiftLeftLogical => {
+            operands.get(1).copied()
+        }
+        _ => None,
+    }
+}
+
+// DXC writes HLSL resource types in lower case, followed by the template arguments, such as
+// "structuredbuffer:<float4>".
+fn user_type(name: &str) -> ReflectUserType {
+    match name.split(':').next().unwrap_or_default() {
+        "cbuffer" => ReflectUserType::Cbuffer,
+        "tbuffer" => ReflectUserType::Tbuffer,
+        "appendstructuredbuffer" => ReflectUserType::AppendStructuredBuffer,
+        "buffer" => ReflectUserType::Buffer,
+        "byteaddressbuffer" => ReflectUserType::ByteAddressBuffer,
+        "constantbuffer" => ReflectUserType::ConstantBuffer,
+        "consumestructuredbuffer" => ReflectUserType::ConsumeStructuredBuffer,
+        "inputpatch" => ReflectUserType::InputPatch,
+        "outputpatch" => ReflectUserType::OutputPatch,
+        "rasterizerorderedbuffer" => ReflectUserType::RasterizerOrderedBuffer,
+        "rasterizerorderedbyteaddressbuffer" => ReflectUserType::RasterizerOrderedByteAddressBuffer,
+        "rasterizerorderedstructuredbuffer" => ReflectUserType::RasterizerOrderedStructuredBuffer,
+        "rasterizerorderedtexture1d" => ReflectUserType::RasterizerOrderedTexture1d,
+        "rasterizerorderedtexture1darray" => ReflectUserType::RasterizerOrderedTexture1dArray,
+        "rasterizerorderedtexture2d" => ReflectUserType::RasterizerOrderedTexture2d,
+        "rasterizerorderedtexture2darray" => ReflectUserType::RasterizerOrderedTexture2dArray,
+        "rasterizerorderedtexture3d" => ReflectUserType::RasterizerOrderedTexture3d,
+        "raytracingaccelerationstructure" => ReflectUserType::RaytracingAccelerationStructure,
+        "rwbuffer" => ReflectUserType::RwBuffer,
+        "rwbyteaddressbuffer" => ReflectUserType::RwByteAddressBuffer,
+        "rwstructuredbuffer" => ReflectUserType::RwStructuredBuffer,
+        "rwtexture1d" => ReflectUserType::RwTexture1d,
+        "rwtexture1darray" => ReflectUserType::RwTexture1dArray,
+        "rwtexture2d" => ReflectUserType::RwTexture2d,
+        "rwtexture2darray" => ReflectUserType::RwTexture2dArray,
+        "rwtexture3d" => ReflectUserType::RwTexture3d,
+        "structuredbuffer" => ReflectUserType::StructuredBuffer,
+        "subpassinput" => ReflectUserType::SubpassInput,
+        "subpassinputms" => ReflectUserType::SubpassInputMs,
+        "texture1d" => ReflectUserType::Texture1d,
+        "texture1darray" => ReflectUserType::Texture1dArray,
+        "texture2d" => ReflectUserType::Texture2d,
+        "texture2darray" => ReflectUserType::Texture2dArray,
+        "texture2dms" => ReflectUserType::Texture2dMs,
+        "texture2dmsarray" => ReflectUserType::Texture2dMsArray,
+        "texture3d" => ReflectUserType::Texture3d,
+        "texturebuffer" => ReflectUserType::TextureBuffer,
+        "texturecube" => ReflectUserType::TextureCube,
+        "texturecubearray" => ReflectUserType::TextureCubeArray,
+        _ => ReflectUserType::Invalid,
+    }
+}
+
+// `operands` starts at the decoration, `word_offset` is the position of its first literal.
+fn apply_decoration(
+    decorations: &mut Decorations,
+    operands: &[u32],
+    word_offset: u32,
+) -> Option<()> {
+    let literal = || operands.get(1).copied();
+    let decoration = match operands.first().copied().and_then(Decoration::from_u32) {
+        Some(decoration) => decoration,
+        None => return Some(()),
+    };
+    match decoration {
+        Decoration::Block => decorations.flags |= ReflectDecorationFlags::BLOCK,
+        Decoration::BufferBlock => decorations.flags |= ReflectDecorationFlags::BUFFER_BLOCK,
+        Decoration::RowMajor => decorations.flags |= ReflectDecorationFlags::ROW_MAJOR,
+        Decoration::ColMajor => decorations.flags |= ReflectDecorationFlags::COLUMN_MAJOR,
+        Decoration::NoPerspective => decorations.flags |= ReflectDecorationFlags::NO_PERSPECTIVE,
+        Decoration::Flat => decorations.flags |= ReflectDecorationFlags::FLAT,
+        Decoration::NonWritable => decorations.flags |= ReflectDecorationFlags::NON_WRITABLE,
+        Decoration::NonReadable => decorations.flags |= ReflectDecorationFlags::NON_READABLE,
+        Decoration::RelaxedPrecision => {
+            decorations.flags |= ReflectDecorationFlags::RELAXED_PRECISION
+        }
+        Decoration::Patch => decorations.flags |= ReflectDecorationFlags::PATCH,
+        Decoration::PerVertexNV => decorations.flags |= ReflectDecorationFlags::PER_VERTEX,
+        Decoration::PerTaskNV => decorations.flags |= ReflectDecorationFlags::PER_TASK,
+        Decoration::BuiltIn => {
+            decorations.flags |= ReflectDecorationFlags::BUILT_IN;
+            decorations.built_in = Some(literal()?);
+        }
+        Decoration::ArrayStride => decorations.array_stride = literal()?,
+        Decoration::MatrixStride => decorations.matrix_stride = literal()?,
+        Decoration::Offset => decorations.offset = literal()?,
+        Decoration::SpecId => decorations.spec_id = Some(literal()?),
+        Decoration::InputAttachmentIndex => decorations.input_attachment_index = Some(literal()?),
+        Decoration::CounterBuffer => decorations.counter_buffer = Some(literal()?),
+        Decoration::UserSemantic => decorations.semantic = decode_string(&operands[1..]),
+        Decoration::UserTypeGOOGLE => {
+            decorations.user_type = user_type(&decode_string(&operands[1..]))
+        }
+        Decoration::Location => {
+            decorations.location = Some(Literal {
+                value: literal()?,
+                word_offset,
+            })
+        }
+        Decoration::Component => decorations.component = Some(literal()?),
+        Decoration::Binding => {
+            decorations.binding = Some(Literal {
+                value: literal()?,
+                word_offset,
+            })
+        }
+        Decoration::DescriptorSet => {
+            decorations.set = Some(Literal {
+                value: literal()?,
+                word_offset,
+            })
+        }
+        _ => {}
+    }
+    Some(())
+}
+
+pub(crate) fn decode_string(words: &[u32]) -> String {
+    let bytes: Vec<u8> = words
+        .iter()
+        .flat_map(|word| word.to_le_bytes().to_vec())
+        .take_while(|&byte| byte != 0)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn string_word_count(words: &[u32]) -> usize {
+    words
+        .iter()
+        .position(|word| word.to_le_bytes().contains(&0))
+        .map_or(words.len(), |index| index + 1)
+}
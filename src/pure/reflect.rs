@@ -0,0 +1,1121 @@
+use super::parse::{decode_string, Decorations, Node, Parser};
+use crate::convert;
+use crate::error::{ReflectError, ReflectErrorKind};
+use crate::parser;
+use crate::types::*;
+use num_traits::cast::FromPrimitive;
+use spirv::{Op, StorageClass};
+use std::collections::HashSet;
+
+const MAX_DESCRIPTOR_SETS: usize = 64;
+const MAX_TYPE_DEPTH: usize = 64;
+const DATA_ALIGNMENT: u32 = 16;
+const IMAGE_SAMPLED: u32 = 1;
+const IMAGE_STORAGE: u32 = 2;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Module {
+    pub(crate) code: Vec<u32>,
+    pub(crate) generator: ReflectGenerator,
+    pub(crate) source_language: u32,
+    pub(crate) source_language_version: u32,
+    pub(crate) source_file: String,
+    pub(crate) source_text: String,
+    pub(crate) capabilities: Vec<ReflectCapability>,
+    pub(crate) entry_points: Vec<ReflectEntryPoint>,
+    // The interface ids of each entry point, in the order `OpEntryPoint` lists them.
+    pub(crate) interfaces: Vec<Vec<u32>>,
+    pub(crate) descriptor_bindings: Vec<ReflectDescriptorBinding>,
+    pub(crate) descriptor_sets: Vec<ReflectDescriptorSet>,
+    pub(crate) push_constant_blocks: Vec<ReflectBlockVariable>,
+    pub(crate) specialization_constants: Vec<ReflectSpecializationConstant>,
+}
+
+impl Module {
+    pub(crate) fn new(code: Vec<u32>) -> Result<Module, ReflectError> {
+        let parser = Parser::new(&code)?;
+
+        let mut module = Module {
+            code: Vec::new(),
+            generator: convert::ffi_to_generator(code[2] >> 16),
+            source_language: 0,
+            source_language_version: 0,
+            source_file: String::new(),
+            source_text: String::new(),
+            capabilities: Vec::new(),
+            entry_points: Vec::new(),
+            interfaces: parser
+                .entry_points
+                .iter()
+                .map(|entry_point| entry_point.interface.clone())
+                .collect(),
+            descriptor_bindings: descriptor_bindings(&parser)?,
+            descriptor_sets: Vec::new(),
+            push_constant_blocks: push_constant_blocks(&parser)?,
+            specialization_constants: specialization_constants(&parser, &code),
+        };
+        for node in &parser.nodes {
+            let operands = node.operands;
+            match node.op() {
+                Some(Op::Capability) => module.capabilities.push(ReflectCapability {
+                    value: spirv::Capability::from_u32(operands[0]),
+                    word_offset: node.word_offset,
+                }),
+                Some(Op::Source) => {
+                    module.source_language = operands[0];
+                    module.source_language_version = operands[1];
+                    if let Some(&file) = operands.get(2) {
+                        module.source_file = parser.string(file);
+                    }
+                    if operands.len() > 3 {
+                        module.source_text = decode_string(&operands[3..]);
+                    }
+                }
+                Some(Op::SourceContinued) => {
+                    module.source_text.push_str(&decode_string(operands));
+                }
+                _ => {}
+            }
+        }
+        module.descriptor_sets = descriptor_sets(&module.descriptor_bindings)?;
+        module.entry_points = entry_points(&parser, &code, &module)?;
+        mark_accessed_bindings(&mut module);
+        drop(parser);
+        module.code = code;
+        Ok(module)
+    }
+
+    pub(crate) fn input_variables(&self) -> &[ReflectInterfaceVariable] {
+        self.entry_points
+            .first()
+            .map_or(&[], |entry_point| &entry_point.input_variables[..])
+    }
+
+    pub(crate) fn output_variables(&self) -> &[ReflectInterfaceVariable] {
+        self.entry_points
+            .first()
+            .map_or(&[], |entry_point| &entry_point.output_variables[..])
+    }
+
+    // Writes new values over decoration literals and reflects the patched code again.
+    pub(crate) fn patch(&mut self, words: &[(u32, u32)]) -> Result<(), ReflectError> {
+        let mut code = self.code.clone();
+        for &(word_offset, value) in words {
+            match code.get_mut(word_offset as usize) {
+                Some(word) => *word = value,
+                None => {
+                    return Err(ReflectError::new(ReflectErrorKind::RangeExceeded)
+                        .with_word_offset(word_offset))
+                }
+            }
+        }
+        *self = Module::new(code)?;
+        Ok(())
+    }
+}
+
+fn shader_stage(execution_model: u32) -> ReflectShaderStageFlags {
+    match execution_model {
+        0 => ReflectShaderStageFlags::VERTEX,
+        1 => ReflectShaderStageFlags::TESSELLATION_CONTROL,
+        2 => ReflectShaderStageFlags::TESSELLATION_EVALUATION,
+        3 => ReflectShaderStageFlags::GEOMETRY,
+        4 => ReflectShaderStageFlags::FRAGMENT,
+        5 => ReflectShaderStageFlags::COMPUTE,
+        5267 | 5364 => ReflectShaderStageFlags::TASK_EXT,
+        5268 | 5365 => ReflectShaderStageFlags::MESH_EXT,
+        5313 => ReflectShaderStageFlags::RAYGEN_BIT_NV,
+        5314 => ReflectShaderStageFlags::INTERSECTION_BIT_NV,
+        5315 => ReflectShaderStageFlags::ANY_HIT_BIT_NV,
+        5316 => ReflectShaderStageFlags::CLOSEST_HIT_BIT_NV,
+        5317 => ReflectShaderStageFlags::MISS_BIT_NV,
+        5318 => ReflectShaderStageFlags::CALLABLE_BIT_NV,
+        _ => ReflectShaderStageFlags::UNDEFINED,
+    }
+}
+
+fn entry_points(
+    parser: &Parser,
+    code: &[u32],
+    module: &Module,
+) -> Result<Vec<ReflectEntryPoint>, ReflectError> {
+    let execution_modes = parser::parse_execution_modes(code);
+    let mut uniforms: Vec<u32> = module
+        .descriptor_bindings
+        .iter()
+        .map(|binding| binding.spirv_id)
+        .collect();
+    uniforms.sort_unstable();
+    let push_constants: Vec<u32> = module
+        .push_constant_blocks
+        .iter()
+        .map(|block| block.spirv_id)
+        .collect();
+
+    let mut entry_points = Vec::with_capacity(parser.entry_points.len());
+    for entry_point in &parser.entry_points {
+        let name = Some(entry_point.name.as_str());
+        let accessed = accessed_variables(parser, entry_point.function_id)
+            .map_err(|err| err.with_entry_point(name))?;
+        let used_uniforms: Vec<u32> = uniforms
+            .iter()
+            .copied()
+            .filter(|id| accessed.binary_search(id).is_ok())
+            .collect();
+        let mut used_push_constants: Vec<u32> = push_constants
+            .iter()
+            .copied()
+            .filter(|id| accessed.binary_search(id).is_ok())
+            .collect();
+        used_push_constants.sort_unstable();
+
+        let descriptor_sets = module
+            .descriptor_sets
+            .iter()
+            .filter_map(|set| {
+                let bindings: Vec<ReflectDescriptorBinding> = set
+                    .bindings
+                    .iter()
+                    .filter(|binding| used_uniforms.binary_search(&binding.spirv_id).is_ok())
+                    .cloned()
+                    .collect();
+                if bindings.is_empty() {
+                    None
+                } else {
+                    Some(ReflectDescriptorSet {
+                        set: set.set,
+                        bindings,
+                    })
+                }
+            })
+            .collect();
+
+        let execution_modes = execution_modes
+            .get(&entry_point.function_id)
+            .cloned()
+            .unwrap_or_default();
+        let mut local_size = ReflectEntryPointLocalSize::default();
+        let mut invocations = 0;
+        let mut output_vertices = 0;
+        for mode in &execution_modes {
+            match *mode {
+                ReflectExecutionMode::LocalSize(size) => local_size = size,
+                ReflectExecutionMode::Invocations(count) => invocations = count,
+                ReflectExecutionMode::OutputVertices(count) => output_vertices = count,
+                _ => {}
+            }
+        }
+
+        entry_points.push(ReflectEntryPoint {
+            name: entry_point.name.clone(),
+            id: entry_point.function_id,
+            spirv_execution_model: spirv::ExecutionModel::from_u32(entry_point.execution_model)
+                .unwrap_or(spirv::ExecutionModel::Vertex),
+            shader_stage: shader_stage(entry_point.execution_model),
+            input_variables: interface_variables(
+                parser,
+                &entry_point.interface,
+                StorageClass::Input,
+            )
+            .map_err(|err| err.with_entry_point(name))?,
+            output_variables: interface_variables(
+                parser,
+                &entry_point.interface,
+                StorageClass::Output,
+            )
+            .map_err(|err| err.with_entry_point(name))?,
+            descriptor_sets,
+            used_uniforms,
+            used_push_constants,
+            local_size,
+            invocations,
+            output_vertices,
+            execution_modes,
+        });
+    }
+    Ok(entry_points)
+}
+
+// A binding is accessed if any entry point uses it.
+fn mark_accessed_bindings(module: &mut Module) {
+    fn mark(binding: &mut ReflectDescriptorBinding, used: &[u32]) {
+        binding.accessed = used.binary_search(&binding.spirv_id).is_ok();
+        if let Some(ref mut counter) = binding.uav_counter_binding {
+            mark(counter, used);
+        }
+    }
+
+    let mut used: Vec<u32> = module
+        .entry_points
+        .iter()
+        .flat_map(|entry_point| entry_point.used_uniforms.iter().copied())
+        .collect();
+    used.sort_unstable();
+    used.dedup();
+    let set_bindings = module
+        .descriptor_sets
+        .iter_mut()
+        .chain(
+            module
+                .entry_points
+                .iter_mut()
+                .flat_map(|entry_point| entry_point.descriptor_sets.iter_mut()),
+        )
+        .flat_map(|set| set.bindings.iter_mut());
+    for binding in module.descriptor_bindings.iter_mut().chain(set_bindings) {
+        mark(binding, &used);
+    }
+}
+
+// Variables referenced by the function and everything it calls, sorted by id.
+fn accessed_variables(parser: &Parser, function_id: u32) -> Result<Vec<u32>, ReflectError> {
+    fn visit(
+        parser: &Parser,
+        function_id: u32,
+        call_stack: &mut Vec<u32>,
+        visited: &mut HashSet<u32>,
+        accessed: &mut Vec<u32>,
+    ) -> Result<(), ReflectError> {
+        if call_stack.contains(&function_id) {
+            return Err(ReflectError::new(ReflectErrorKind::SpirvRecursion));
+        }
+        if !visited.insert(function_id) {
+            return Ok(());
+        }
+        let function = parser
+            .functions
+            .get(&function_id)
+            .ok_or_else(|| ReflectError::new(ReflectErrorKind::SpirvInvalidIdReference))?;
+        accessed.extend_from_slice(&function.accessed);
+        call_stack.push(function_id);
+        for &callee in &function.callees {
+            visit(parser, callee, call_stack, visited, accessed)?;
+        }
+        call_stack.pop();
+        Ok(())
+    }
+
+    let mut accessed = Vec::new();
+    visit(
+        parser,
+        function_id,
+        &mut Vec::new(),
+        &mut HashSet::new(),
+        &mut accessed,
+    )?;
+    accessed.sort_unstable();
+    accessed.dedup();
+    Ok(accessed)
+}
+
+fn variables<'p, 'a>(
+    parser: &'p Parser<'a>,
+    storage_classes: &'p [StorageClass],
+) -> impl Iterator<Item = &'p Node<'a>> {
+    parser.nodes.iter().filter(move |node| {
+        node.op() == Some(Op::Variable)
+            && storage_classes
+                .iter()
+                .any(|&storage_class| node.operands[2] == storage_class as u32)
+    })
+}
+
+fn empty_type_description(
+    id: u32,
+    opcode: u32,
+    storage_class: ReflectStorageClass,
+) -> ReflectTypeDescription {
+    ReflectTypeDescription {
+        id,
+        op: ReflectOp::from(opcode),
+        storage_class,
+        traits: ReflectTypeDescriptionTraits {
+            image: ReflectImageTraits {
+                dim: convert::ffi_to_dimension(0),
+                image_format: convert::ffi_to_image_format(0),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn type_description(parser: &Parser, id: u32) -> Result<ReflectTypeDescription, ReflectError> {
+    let node = parser.node(id)?;
+    let mut description = empty_type_description(id, node.opcode, ReflectStorageClass::Undefined);
+    parse_type(parser, id, None, &mut description, &mut Vec::new())?;
+    Ok(description)
+}
+
+// Type descriptions of variables describe the pointee, like SPIRV-Reflect does.
+fn variable_type(parser: &Parser, variable: &Node) -> Result<ReflectTypeDescription, ReflectError> {
+    let pointer = parser
+        .node(variable.operands[0])
+        .map_err(|err| err.with_word_offset(variable.word_offset))?;
+    if pointer.op() != Some(Op::TypePointer) {
+        return Err(ReflectError::new(ReflectErrorKind::SpirvInvalidIdReference)
+            .with_word_offset(variable.word_offset));
+    }
+    type_description(parser, pointer.operands[2])
+        .map_err(|err| err.with_word_offset(pointer.word_offset))
+}
+
+// Merges the traits of `id` and of the types it is built from into `description`.
+fn parse_type(
+    parser: &Parser,
+    id: u32,
+    member_decorations: Option<&Decorations>,
+    description: &mut ReflectTypeDescription,
+    type_stack: &mut Vec<u32>,
+) -> Result<(), ReflectError> {
+    if type_stack.len() >= MAX_TYPE_DEPTH {
+        return Err(ReflectError::new(
+            ReflectErrorKind::SpirvMaxRecursiveExceeded,
+        ));
+    }
+    let node = parser.node(id)?;
+    let operands = node.operands;
+    let decorations = parser.decorations(id);
+    if description.type_name.is_empty() {
+        description.type_name = parser.name(id);
+    }
+    description.decoration_flags |= decorations.flags;
+
+    type_stack.push(id);
+    match node.op() {
+        Some(Op::TypeVoid) => description.type_flags |= ReflectTypeFlags::VOID,
+        Some(Op::TypeBool) => description.type_flags |= ReflectTypeFlags::BOOL,
+        Some(Op::TypeInt) => {
+            description.type_flags |= ReflectTypeFlags::INT;
+            description.traits.numeric.scalar.width = operands[1];
+            description.traits.numeric.scalar.signedness = operands[2];
+        }
+        Some(Op::TypeFloat) => {
+            description.type_flags |= ReflectTypeFlags::FLOAT;
+            description.traits.numeric.scalar.width = operands[1];
+        }
+        Some(Op::TypeVector) => {
+            description.type_flags |= ReflectTypeFlags::VECTOR;
+            description.traits.numeric.vector.component_count = operands[2];
+            parse_type(parser, operands[1], None, description, type_stack)?;
+        }
+        Some(Op::TypeMatrix) => {
+            description.type_flags |= ReflectTypeFlags::MATRIX;
+            description.traits.numeric.matrix.column_count = operands[2];
+            parse_type(parser, operands[1], None, description, type_stack)?;
+            let numeric = &mut description.traits.numeric;
+            numeric.matrix.row_count = numeric.vector.component_count;
+            // Matrix strides are member decorations of the enclosing struct.
+            numeric.matrix.stride = member_decorations.unwrap_or(decorations).matrix_stride;
+        }
+        Some(Op::TypeImage) => {
+            description.type_flags |= ReflectTypeFlags::EXTERNAL_IMAGE;
+            parse_type(parser, operands[1], None, description, type_stack)?;
+            description.traits.image = ReflectImageTraits {
+                dim: convert::ffi_to_dimension(operands[2]),
+                depth: operands[3],
+                arrayed: operands[4],
+                ms: operands[5],
+                sampled: operands[6],
+                image_format: convert::ffi_to_image_format(operands[7]),
+            };
+        }
+        Some(Op::TypeSampler) => description.type_flags |= ReflectTypeFlags::EXTERNAL_SAMPLER,
+        Some(Op::TypeSampledImage) => {
+            description.type_flags |= ReflectTypeFlags::EXTERNAL_SAMPLED_IMAGE;
+            parse_type(parser, operands[1], None, description, type_stack)?;
+        }
+        Some(Op::TypeArray) => {
+            description.type_flags |= ReflectTypeFlags::ARRAY;
+            description.traits.array.stride = decorations.array_stride;
+            let length = parser.node(operands[2])?;
+            let dim = match length.op() {
+                Some(Op::SpecConstant) | Some(Op::SpecConstantOp) => u32::MAX,
+                _ => *length
+                    .operands
+                    .get(2)
+                    .ok_or_else(|| ReflectError::new(ReflectErrorKind::SpirvInvalidIdReference))?,
+            };
+            description.traits.array.dims.push(dim);
+            parse_type(parser, operands[1], None, description, type_stack)?;
+        }
+        Some(Op::TypeRuntimeArray) => {
+            description.type_flags |= ReflectTypeFlags::ARRAY;
+            description.traits.array.stride = decorations.array_stride;
+            description.traits.array.dims.push(0);
+            parse_type(parser, operands[1], None, description, type_stack)?;
+        }
+        Some(Op::TypeStruct) => {
+            description.type_flags |= ReflectTypeFlags::STRUCT | ReflectTypeFlags::EXTERNAL_BLOCK;
+            description.members.clear();
+            for (index, &member_id) in operands[1..].iter().enumerate() {
+                let index = index as u32;
+                let mut member = empty_type_description(
+                    member_id,
+                    parser.node(member_id)?.opcode,
+                    ReflectStorageClass::UniformConstant,
+                );
+                parse_type(
+                    parser,
+                    member_id,
+                    Some(parser.member_decorations(id, index)),
+                    &mut member,
+                    type_stack,
+                )?;
+                member.struct_member_name = parser.member_name(id, index);
+                description.members.push(member);
+            }
+        }
+        Some(Op::TypePointer) => {
+            description.storage_class = convert::ffi_to_storage_class(operands[1]);
+            if operands[1] == StorageClass::PhysicalStorageBuffer as u32 {
+                description.type_flags |= ReflectTypeFlags::REF;
+            }
+            // Physical storage buffer pointers may point back at an enclosing struct.
+            if !type_stack.contains(&operands[2]) {
+                parse_type(parser, operands[2], None, description, type_stack)?;
+            }
+        }
+        Some(Op::TypeAccelerationStructureNV) => {
+            description.type_flags |= ReflectTypeFlags::EXTERNAL_ACCELERATION_STRUCTURE_NV
+        }
+        _ => {}
+    }
+    type_stack.pop();
+    Ok(())
+}
+
+// Member names and decorations live on the struct, also when it is the element of an array.
+fn struct_type_id(parser: &Parser, mut id: u32) -> u32 {
+    while let Ok(node) = parser.node(id) {
+        match node.op() {
+            Some(Op::TypeArray) | Some(Op::TypeRuntimeArray) => id = node.operands[1],
+            _ => break,
+        }
+    }
+    id
+}
+
+fn interface_variables(
+    parser: &Parser,
+    interface: &[u32],
+    storage_class: StorageClass,
+) -> Result<Vec<ReflectInterfaceVariable>, ReflectError> {
+    let mut variables = Vec::new();
+    for &id in interface {
+        let node = parser.node(id)?;
+        if node.op() != Some(Op::Variable) {
+            return Err(ReflectError::new(ReflectErrorKind::SpirvInvalidIdReference)
+                .with_word_offset(node.word_offset));
+        }
+        if node.operands[2] != storage_class as u32 {
+            continue;
+        }
+        let type_description = variable_type(parser, node)?;
+        let decorations = parser.decorations(id);
+        let mut has_built_in = false;
+        let mut variable =
+            interface_variable(parser, decorations, &type_description, &mut has_built_in);
+        variable.spirv_id = id;
+        variable.name = parser.name(id);
+        variable.storage_class = convert::ffi_to_storage_class(storage_class as u32);
+        variable.semantic = decorations.semantic.clone();
+        if has_built_in {
+            variable.decoration_flags |= ReflectDecorationFlags::BUILT_IN;
+        }
+        // Locations are decorated on the variable, not on its type.
+        if let Some(location) = decorations.location {
+            variable.location = location.value;
+            variable.word_offset = location.word_offset;
+        } else {
+            variable.location = u32::MAX;
+        }
+        variable.component = decorations.component.unwrap_or(u32::MAX);
+        variables.push(variable);
+    }
+    Ok(variables)
+}
+
+fn interface_variable(
+    parser: &Parser,
+    decorations: &Decorations,
+    type_description: &ReflectTypeDescription,
+    has_built_in: &mut bool,
+) -> ReflectInterfaceVariable {
+    let struct_id = struct_type_id(parser, type_description.id);
+    let members = type_description
+        .members
+        .iter()
+        .enumerate()
+        .map(|(index, member)| {
+            let index = index as u32;
+            let decorations = parser.member_decorations(struct_id, index);
+            let mut variable = interface_variable(parser, decorations, member, has_built_in);
+            variable.name = parser.member_name(struct_id, index);
+            variable.location = decorations
+                .location
+                .as_ref()
+                .map_or(u32::MAX, |location| location.value);
+            variable.component = decorations.component.unwrap_or(u32::MAX);
+            variable
+        })
+        .collect();
+    *has_built_in |= decorations.built_in.is_some();
+
+    // Matrices take the format of a single column.
+    let column = ReflectNumericTraits {
+        matrix: ReflectNumericTraitsMatrix::default(),
+        ..type_description.traits.numeric
+    };
+    ReflectInterfaceVariable {
+        spirv_id: 0,
+        name: String::new(),
+        location: 0,
+        component: 0,
+        storage_class: convert::ffi_to_storage_class(0),
+        semantic: String::new(),
+        decoration_flags: decorations.flags,
+        built_in: ReflectBuiltIn::from(decorations.built_in.unwrap_or(u32::MAX)),
+        numeric: type_description.traits.numeric,
+        array: if *type_description.op == Op::TypeArray {
+            type_description.traits.array.clone()
+        } else {
+            ReflectArrayTraits::default()
+        },
+        members,
+        format: ReflectFormat::from_numeric_traits(&column, type_description.type_flags),
+        type_description: Some(type_description.clone()),
+        word_offset: 0,
+    }
+}
+
+fn block_variable(
+    parser: &Parser,
+    type_description: &ReflectTypeDescription,
+) -> ReflectBlockVariable {
+    ReflectBlockVariable {
+        members: block_members(parser, type_description),
+        type_description: Some(type_description.clone()),
+        ..Default::default()
+    }
+}
+
+fn block_members(
+    parser: &Parser,
+    type_description: &ReflectTypeDescription,
+) -> Vec<ReflectBlockVariable> {
+    let struct_id = struct_type_id(parser, type_description.id);
+    type_description
+        .members
+        .iter()
+        .enumerate()
+        .map(|(index, member)| {
+            let index = index as u32;
+            let decorations = parser.member_decorations(struct_id, index);
+            ReflectBlockVariable {
+                spirv_id: 0,
+                name: parser.member_name(struct_id, index),
+                offset: decorations.offset,
+                absolute_offset: 0,
+                size: 0,
+                padded_size: 0,
+                decoration_flags: decorations.flags,
+                numeric: member.traits.numeric,
+                array: if *member.op == Op::TypeArray {
+                    member.traits.array.clone()
+                } else {
+                    ReflectArrayTraits::default()
+                },
+                flags: ReflectVariableFlags::UNUSED,
+                members: block_members(parser, member),
+                type_description: Some(member.clone()),
+            }
+        })
+        .collect()
+}
+
+// Like SPIRV-Reflect, members are used if an access chain into the block reaches them or one
+// of their parents. Loading or passing the whole block doesn't count.
+fn parse_block_usage(
+    parser: &Parser,
+    variable_id: u32,
+    array_index_count: usize,
+    block: &mut ReflectBlockVariable,
+) {
+    for node in &parser.nodes {
+        let first_index = match node.op() {
+            Some(Op::AccessChain) | Some(Op::InBoundsAccessChain) => 3,
+            Some(Op::PtrAccessChain) | Some(Op::InBoundsPtrAccessChain) => 4,
+            _ => continue,
+        };
+        if node.operands.get(2) != Some(&variable_id) {
+            continue;
+        }
+        let indexes = node
+            .operands
+            .get(first_index + array_index_count..)
+            .unwrap_or(&[]);
+        mark_used_members(parser, &mut block.members, indexes);
+    }
+}
+
+fn mark_used_members(parser: &Parser, members: &mut [ReflectBlockVariable], indexes: &[u32]) {
+    let (&index, indexes) = match indexes.split_first() {
+        Some(split) => split,
+        None => {
+            for member in members {
+                member.flags.remove(ReflectVariableFlags::UNUSED);
+                mark_used_members(parser, &mut member.members, &[]);
+            }
+            return;
+        }
+    };
+    // Struct members can only be selected with constants.
+    let member = match parser
+        .node(index)
+        .ok()
+        .filter(|node| node.op() == Some(Op::Constant))
+        .and_then(|node| node.operands.get(2))
+        .and_then(|&index| members.get_mut(index as usize))
+    {
+        Some(member) => member,
+        None => return,
+    };
+    member.flags.remove(ReflectVariableFlags::UNUSED);
+    let array_index_count = member
+        .type_description
+        .as_ref()
+        .map_or(0, |type_description| {
+            type_description.traits.array.dims.len()
+        });
+    let indexes = indexes.get(array_index_count..).unwrap_or(&[]);
+    mark_used_members(parser, &mut member.members, indexes);
+}
+
+// Sizes and offsets come straight from decorations, so a malformed module could overflow them.
+fn checked(value: Option<u32>) -> Result<u32, ReflectError> {
+    value.ok_or_else(|| ReflectError::new(ReflectErrorKind::RangeExceeded))
+}
+
+fn parse_block_sizes(
+    block: &mut ReflectBlockVariable,
+    is_parent_rta: bool,
+) -> Result<(), ReflectError> {
+    if block.members.is_empty() {
+        return Ok(());
+    }
+
+    for member in &mut block.members {
+        let (op, type_flags) = match member.type_description {
+            Some(ref type_description) => (*type_description.op, type_description.type_flags),
+            None => continue,
+        };
+        let is_struct = type_flags.contains(ReflectTypeFlags::STRUCT);
+        let numeric = member.numeric;
+        match op {
+            Op::TypeBool => member.size = 4,
+            Op::TypeInt | Op::TypeFloat => member.size = numeric.scalar.width / 8,
+            Op::TypeVector => {
+                member.size = checked(
+                    numeric
+                        .vector
+                        .component_count
+                        .checked_mul(numeric.scalar.width / 8),
+                )?
+            }
+            Op::TypeMatrix => {
+                if member
+                    .decoration_flags
+                    .contains(ReflectDecorationFlags::COLUMN_MAJOR)
+                {
+                    member.size = checked(
+                        numeric
+                            .matrix
+                            .column_count
+                            .checked_mul(numeric.matrix.stride),
+                    )?;
+                } else if member
+                    .decoration_flags
+                    .contains(ReflectDecorationFlags::ROW_MAJOR)
+                {
+                    member.size =
+                        checked(numeric.matrix.row_count.checked_mul(numeric.matrix.stride))?;
+                }
+            }
+            Op::TypeArray => {
+                if is_struct {
+                    parse_block_sizes(member, is_parent_rta)?;
+                }
+                let element_count = member
+                    .array
+                    .dims
+                    .iter()
+                    .try_fold(1u32, |count, &dim| count.checked_mul(dim));
+                member.size = checked(
+                    element_count.and_then(|count| count.checked_mul(member.array.stride)),
+                )?;
+            }
+            Op::TypeRuntimeArray if is_struct => parse_block_sizes(member, true)?,
+            Op::TypeStruct => parse_block_sizes(member, is_parent_rta)?,
+            _ => {}
+        }
+    }
+
+    // Members are padded up to the next member, the last one up to the data alignment.
+    let member_count = block.members.len();
+    for index in 0..member_count {
+        let offset = block.members[index].offset;
+        let padded_size = match block.members.get(index + 1) {
+            Some(next) => next.offset.saturating_sub(offset),
+            None => {
+                let end = checked(
+                    offset
+                        .checked_add(block.members[index].size)
+                        .and_then(|end| end.checked_add(DATA_ALIGNMENT - 1)),
+                )?;
+                (end & !(DATA_ALIGNMENT - 1)) - offset
+            }
+        };
+        let member = &mut block.members[index];
+        member.padded_size = padded_size;
+        if member.size > member.padded_size {
+            member.size = member.padded_size;
+        }
+        if is_parent_rta {
+            member.padded_size = member.size;
+        }
+    }
+
+    let last = &block.members[member_count - 1];
+    block.size = checked(last.offset.checked_add(last.padded_size))?;
+    block.padded_size = block.size;
+    Ok(())
+}
+
+fn parse_absolute_offsets(
+    block: &mut ReflectBlockVariable,
+    is_parent_root: bool,
+    is_parent_aos: bool,
+) -> Result<(), ReflectError> {
+    let parent_offset = block.absolute_offset;
+    for member in &mut block.members {
+        member.absolute_offset = if is_parent_root {
+            member.offset
+        } else if is_parent_aos {
+            0
+        } else {
+            checked(parent_offset.checked_add(member.offset))?
+        };
+        let is_array = member
+            .type_description
+            .as_ref()
+            .is_some_and(|type_description| {
+                type_description
+                    .type_flags
+                    .contains(ReflectTypeFlags::ARRAY)
+            });
+        parse_absolute_offsets(member, false, is_array)?;
+    }
+    Ok(())
+}
+
+fn descriptor_type(
+    type_description: &ReflectTypeDescription,
+    storage_class: u32,
+) -> ReflectDescriptorType {
+    let image = &type_description.traits.image;
+    let texel_buffer = || match image.sampled {
+        IMAGE_SAMPLED => ReflectDescriptorType::UniformTexelBuffer,
+        IMAGE_STORAGE => ReflectDescriptorType::StorageTexelBuffer,
+        _ => ReflectDescriptorType::Undefined,
+    };
+    let external = type_description.type_flags & ReflectTypeFlags::EXTERNAL_MASK;
+    if external == ReflectTypeFlags::EXTERNAL_BLOCK {
+        if storage_class == StorageClass::StorageBuffer as u32
+            || type_description
+                .decoration_flags
+                .contains(ReflectDecorationFlags::BUFFER_BLOCK)
+        {
+            ReflectDescriptorType::StorageBuffer
+        } else if type_description
+            .decoration_flags
+            .contains(ReflectDecorationFlags::BLOCK)
+        {
+            ReflectDescriptorType::UniformBuffer
+        } else {
+            ReflectDescriptorType::Undefined
+        }
+    } else if external
+        == ReflectTypeFlags::EXTERNAL_SAMPLED_IMAGE | ReflectTypeFlags::EXTERNAL_IMAGE
+    {
+        if image.dim == ReflectDimension::Buffer {
+            texel_buffer()
+        } else {
+            ReflectDescriptorType::CombinedImageSampler
+        }
+    } else if external == ReflectTypeFlags::EXTERNAL_IMAGE {
+        match image.dim {
+            ReflectDimension::Buffer => texel_buffer(),
+            ReflectDimension::SubPassData => ReflectDescriptorType::InputAttachment,
+            _ => match image.sampled {
+                IMAGE_SAMPLED => ReflectDescriptorType::SampledImage,
+                IMAGE_STORAGE => ReflectDescriptorType::StorageImage,
+                _ => ReflectDescriptorType::Undefined,
+            },
+        }
+    } else if external == ReflectTypeFlags::EXTERNAL_SAMPLER {
+        ReflectDescriptorType::Sampler
+    } else if external == ReflectTypeFlags::EXTERNAL_ACCELERATION_STRUCTURE_NV {
+        ReflectDescriptorType::AccelerationStructureKHR
+    } else {
+        ReflectDescriptorType::Undefined
+    }
+}
+
+fn resource_type(descriptor_type: ReflectDescriptorType) -> ReflectResourceType {
+    match descriptor_type {
+        ReflectDescriptorType::Sampler => ReflectResourceType::Sampler,
+        ReflectDescriptorType::CombinedImageSampler => ReflectResourceType::CombinedImageSampler,
+        ReflectDescriptorType::SampledImage
+        | ReflectDescriptorType::UniformTexelBuffer
+        | ReflectDescriptorType::AccelerationStructureKHR => {
+            ReflectResourceType::ShaderResourceView
+        }
+        ReflectDescriptorType::StorageImage
+        | ReflectDescriptorType::StorageTexelBuffer
+        | ReflectDescriptorType::StorageBuffer
+        | ReflectDescriptorType::StorageBufferDynamic => ReflectResourceType::UnorderedAccessView,
+        ReflectDescriptorType::UniformBuffer | ReflectDescriptorType::UniformBufferDynamic => {
+            ReflectResourceType::ConstantBufferView
+        }
+        _ => ReflectResourceType::Undefined,
+    }
+}
+
+fn descriptor_bindings(parser: &Parser) -> Result<Vec<ReflectDescriptorBinding>, ReflectError> {
+    let storage_classes = [
+        StorageClass::UniformConstant,
+        StorageClass::Uniform,
+        StorageClass::StorageBuffer,
+    ];
+    let mut bindings = Vec::new();
+    for node in variables(parser, &storage_classes) {
+        let id = node.operands[1];
+        let decorations = parser.decorations(id);
+        let (set, binding) = match (decorations.set, decorations.binding) {
+            (Some(set), Some(binding)) => (set, binding),
+            _ => continue,
+        };
+        let type_description = variable_type(parser, node)?;
+        let descriptor_type = descriptor_type(&type_description, node.operands[2]);
+        let name = parser.name(id);
+
+        let mut block = ReflectBlockVariable::default();
+        if descriptor_type == ReflectDescriptorType::UniformBuffer
+            || descriptor_type == ReflectDescriptorType::StorageBuffer
+        {
+            let is_storage_buffer = descriptor_type == ReflectDescriptorType::StorageBuffer;
+            block = block_variable(parser, &type_description);
+            block.name = name.clone();
+            parse_block_usage(
+                parser,
+                id,
+                type_description.traits.array.dims.len(),
+                &mut block,
+            );
+            parse_block_sizes(&mut block, is_storage_buffer)?;
+            parse_absolute_offsets(&mut block, true, false)?;
+            if is_storage_buffer {
+                block.size = 0;
+                block.padded_size = 0;
+            }
+        }
+
+        let array = if type_description
+            .type_flags
+            .contains(ReflectTypeFlags::ARRAY)
+        {
+            type_description.traits.array.dims.clone()
+        } else {
+            Vec::new()
+        };
+        let byte_address_buffer_offsets = match decorations.user_type {
+            ReflectUserType::ByteAddressBuffer
+            | ReflectUserType::RwByteAddressBuffer
+            | ReflectUserType::RasterizerOrderedByteAddressBuffer => {
+                byte_address_buffer_offsets(parser, id, array.len())
+            }
+            _ => Vec::new(),
+        };
+        let access = convert::descriptor_access(descriptor_type, decorations.flags, &block);
+        bindings.push(ReflectDescriptorBinding {
+            spirv_id: id,
+            name,
+            binding: binding.value,
+            input_attachment_index: decorations.input_attachment_index.unwrap_or(0),
+            set: set.value,
+            descriptor_type,
+            resource_type: resource_type(descriptor_type),
+            image: type_description.traits.image,
+            block,
+            count: array
+                .iter()
+                .fold(1u32, |count, &dim| count.wrapping_mul(dim)),
+            array: ReflectBindingArrayTraits { dims: array },
+            // Set once the entry points are known.
+            accessed: false,
+            uav_counter_id: decorations.counter_buffer.unwrap_or(u32::MAX),
+            uav_counter_binding: None,
+            byte_address_buffer_offsets,
+            type_description: Some(type_description),
+            word_offset: (binding.word_offset, set.word_offset),
+            decoration_flags: decorations.flags,
+            user_type: decorations.user_type,
+            access,
+        });
+    }
+    bindings.sort_by_key(|binding| (binding.binding, binding.spirv_id));
+
+    // HLSL append/consume buffers without a counter decoration find theirs by name.
+    for index in 0..bindings.len() {
+        if bindings[index].uav_counter_id == u32::MAX {
+            let counter_names = [
+                format!("{}@count", bindings[index].name),
+                format!("counter.var.{}", bindings[index].name),
+            ];
+            if let Some(counter) = bindings
+                .iter()
+                .find(|binding| counter_names.contains(&binding.name))
+            {
+                bindings[index].uav_counter_id = counter.spirv_id;
+            }
+        }
+        let counter_id = bindings[index].uav_counter_id;
+        bindings[index].uav_counter_binding = bindings
+            .iter()
+            .find(|binding| binding.spirv_id == counter_id)
+            .map(|binding| Box::new(binding.clone()));
+    }
+    Ok(bindings)
+}
+
+// DXC lowers ByteAddressBuffers to a struct with a single uint runtime array, which loads and
+// stores index with the byte address shifted right by two.
+fn byte_address_buffer_offsets(
+    parser: &Parser,
+    variable_id: u32,
+    array_index_count: usize,
+) -> Vec<u32> {
+    let mut offsets: Vec<u32> = parser
+        .nodes
+        .iter()
+        .filter(|node| {
+            matches!(
+                node.op(),
+                Some(Op::AccessChain) | Some(Op::InBoundsAccessChain)
+            ) && node.operands.get(2) == Some(&variable_id)
+        })
+        .filter_map(|node| {
+            let indexes = node.operands.get(3 + array_index_count..)?;
+            match *indexes {
+                [_, index] => constant_value(parser, index, 0),
+                _ => None,
+            }
+        })
+        .map(|index| index.wrapping_mul(4))
+        .collect();
+    offsets.sort_unstable();
+    offsets.dedup();
+    offsets
+}
+
+// Folds the integer arithmetic DXC emits for addresses, without specialization.
+fn constant_value(parser: &Parser, id: u32, depth: usize) -> Option<u32> {
+    if depth >= MAX_TYPE_DEPTH {
+        return None;
+    }
+    let node = parser.node(id).ok()?;
+    let operands = node.operands;
+    let operand = |index: usize| constant_value(parser, *operands.get(index)?, depth + 1);
+    match node.op()? {
+        Op::Constant => operands.get(2).copied(),
+        Op::IAdd => Some(operand(2)?.wrapping_add(operand(3)?)),
+        Op::IMul => Some(operand(2)?.wrapping_mul(operand(3)?)),
+        Op::ShiftRightLogical => operand(2)?.checked_shr(operand(3)?),
+        Op::ShiftLeftLogical => operand(2)?.checked_shl(operand(3)?),
+        _ => None,
+    }
+}
+
+fn descriptor_sets(
+    bindings: &[ReflectDescriptorBinding],
+) -> Result<Vec<ReflectDescriptorSet>, ReflectError> {
+    let mut sets: Vec<ReflectDescriptorSet> = Vec::new();
+    for binding in bindings {
+        match sets.iter_mut().find(|set| set.set == binding.set) {
+            Some(set) => set.bindings.push(binding.clone()),
+            None => sets.push(ReflectDescriptorSet {
+                set: binding.set,
+                bindings: vec![binding.clone()],
+            }),
+        }
+    }
+    if sets.len() > MAX_DESCRIPTOR_SETS {
+        return Err(ReflectError::new(ReflectErrorKind::SpirvSetNumberOverflow));
+    }
+    sets.sort_by_key(|set| set.set);
+    Ok(sets)
+}
+
+fn push_constant_blocks(parser: &Parser) -> Result<Vec<ReflectBlockVariable>, ReflectError> {
+    let mut blocks = Vec::new();
+    for node in variables(parser, &[StorageClass::PushConstant]) {
+        let id = node.operands[1];
+        let type_description = variable_type(parser, node)?;
+        let mut block = block_variable(parser, &type_description);
+        block.spirv_id = id;
+        block.name = parser.name(id);
+        parse_block_usage(parser, id, 0, &mut block);
+        if block.name.is_empty() {
+            block.name = type_description.type_name.clone();
+        }
+        parse_block_sizes(&mut block, false)?;
+        parse_absolute_offsets(&mut block, true, false)?;
+        block.offset = block
+            .members
+            .iter()
+            .map(|member| member.offset)
+            .min()
+            .unwrap_or(0);
+        blocks.push(block);
+    }
+    Ok(blocks)
+}
+
+fn specialization_constants(parser: &Parser, code: &[u32]) -> Vec<ReflectSpecializationConstant> {
+    let defaults = parser::parse_specialization_constant_defaults(code);
+    parser
+        .nodes
+        .iter()
+        .filter(|node| {
+            matches!(
+                node.op(),
+                Some(Op::SpecConstantTrue) | Some(Op::SpecConstantFalse) | Some(Op::SpecConstant)
+            )
+        })
+        .filter_map(|node| {
+            let id = node.operands[1];
+            let constant_id = parser.decorations(id).spec_id?;
+            let default = defaults.get(&id).copied().unwrap_or_default();
+            Some(ReflectSpecializationConstant {
+                spirv_id: id,
+                constant_id,
+                name: parser.name(id),
+                type_flags: default.type_flags,
+                scalar: default.scalar,
+                default_value: default.value,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,330 @@
+use crate::error::ReflectError;
+use crate::types::{ReflectDescriptorBinding, ReflectDescriptorType};
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum BindingMatch {
+    Name(String),
+    Binding { set: u32, binding: u32 },
+    Set(u32),
+    DescriptorType(ReflectDescriptorType),
+}
+
+impl BindingMatch {
+    // More specific matches take precedence, so a table can move a whole register space and
+    // still place individual bindings.
+    fn specificity(&self) -> u32 {
+        match self {
+            BindingMatch::Name(_) => 3,
+            BindingMatch::Binding { .. } => 2,
+            BindingMatch::Set(_) => 1,
+            BindingMatch::DescriptorType(_) => 0,
+        }
+    }
+
+    fn matches(&self, binding: &ReflectDescriptorBinding) -> bool {
+        match self {
+            BindingMatch::Name(name) => &binding.name == name,
+            BindingMatch::Binding {
+                set,
+                binding: binding_number,
+            } => binding.set == *set && binding.binding == *binding_number,
+            BindingMatch::Set(set) => binding.set == *set,
+            BindingMatch::DescriptorType(descriptor_type) => {
+                binding.descriptor_type == *descriptor_type
+            }
+        }
+    }
+}
+
+// `None` keeps the current value.
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RemapTarget {
+    pub set: Option<u32>,
+    pub binding: Option<u32>,
+}
+
+impl RemapTarget {
+    pub fn new(set: u32, binding: u32) -> Self {
+        RemapTarget {
+            set: Some(set),
+            binding: Some(binding),
+        }
+    }
+
+    pub fn set(set: u32) -> Self {
+        RemapTarget {
+            set: Some(set),
+            binding: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RemapRule {
+    pub matcher: BindingMatch,
+    pub target: RemapTarget,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RemapTable {
+    pub rules: Vec<RemapRule>,
+}
+
+impl RemapTable {
+    pub fn new() -> Self {
+        RemapTable::default()
+    }
+
+    pub fn add_rule(&mut self, matcher: BindingMatch, target: RemapTarget) -> &mut Self {
+        self.rules.push(RemapRule { matcher, target });
+        self
+    }
+
+    pub fn by_name(&mut self, name: &str, target: RemapTarget) -> &mut Self {
+        self.add_rule(BindingMatch::Name(name.to_owned()), target)
+    }
+
+    pub fn by_binding(&mut self, set: u32, binding: u32, target: RemapTarget) -> &mut Self {
+        self.add_rule(BindingMatch::Binding { set, binding }, target)
+    }
+
+    pub fn by_set(&mut self, set: u32, target: RemapTarget) -> &mut Self {
+        self.add_rule(BindingMatch::Set(set), target)
+    }
+
+    pub fn by_descriptor_type(
+        &mut self,
+        descriptor_type: ReflectDescriptorType,
+        target: RemapTarget,
+    ) -> &mut Self {
+        self.add_rule(BindingMatch::DescriptorType(descriptor_type), target)
+    }
+
+    // Among the matching rules, the most specific one wins, then the first one added.
+    pub fn find_rule(&self, binding: &ReflectDescriptorBinding) -> Option<&RemapRule> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.matcher.matches(binding))
+            .fold(None, |best: Option<&RemapRule>, rule| match best {
+                Some(best) if best.matcher.specificity() >= rule.matcher.specificity() => {
+                    Some(best)
+                }
+                _ => Some(rule),
+            })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RemappedBinding {
+    pub name: String,
+    pub set: u32,
+    pub binding: u32,
+    pub new_set: u32,
+    pub new_binding: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UnmatchedBinding {
+    pub name: String,
+    pub set: u32,
+    pub binding: u32,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RemapReport {
+    pub remapped: Vec<RemappedBinding>,
+    pub unmatched: Vec<UnmatchedBinding>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RemapCollision {
+    pub set: u32,
+    pub binding: u32,
+    pub names: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum RemapError {
+    Collisions(Vec<RemapCollision>),
+    Reflect(ReflectError),
+}
+
+impl fmt::Display for RemapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RemapError::Collisions(collisions) => {
+                write!(f, "remapping creates binding collisions:")?;
+                for collision in collisions {
+                    write!(
+                        f,
+                        " set {} binding {} ({})",
+                        collision.set,
+                        collision.binding,
+                        collision.names.join(", ")
+                    )?;
+                }
+                Ok(())
+            }
+            RemapError::Reflect(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for RemapError {}
+
+impl From<ReflectError> for RemapError {
+    fn from(err: ReflectError) -> Self {
+        RemapError::Reflect(err)
+    }
+}
+
+#[cfg(feature = "c-backend")]
+impl crate::ShaderModule {
+    pub fn remap_bindings(&mut self, table: &RemapTable) -> Result<RemapReport, RemapError> {
+        let bindings = self.enumerate_descriptor_bindings(None)?;
+        let (report, changes) = plan_remap(&bindings, table)?;
+        self.patch_descriptor_bindings(&changes)?;
+        Ok(report)
+    }
+}
+
+#[cfg(feature = "pure-rust")]
+impl crate::pure::ShaderModule {
+    pub fn remap_bindings(&mut self, table: &RemapTable) -> Result<RemapReport, RemapError> {
+        let bindings = self.enumerate_descriptor_bindings(None)?;
+        let (report, changes) = plan_remap(&bindings, table)?;
+        self.patch_descriptor_bindings(&changes)?;
+        Ok(report)
+    }
+}
+
+// Each moved binding with its new binding and set, in the form both backends patch.
+type RemapChanges<'a> = Vec<(&'a ReflectDescriptorBinding, u32, u32)>;
+
+// Nothing is patched if the remap would make two bindings share a slot. Bindings that
+// already shared a slot before the remap and aren't moved are left alone.
+fn plan_remap<'a>(
+    bindings: &'a [ReflectDescriptorBinding],
+    table: &RemapTable,
+) -> Result<(RemapReport, RemapChanges<'a>), RemapError> {
+    let mut report = RemapReport::default();
+    let mut changes = Vec::new();
+    let mut slots: BTreeMap<(u32, u32), Vec<(&ReflectDescriptorBinding, bool)>> = BTreeMap::new();
+    for binding in bindings {
+        let (new_set, new_binding, moved) = match table.find_rule(binding) {
+            Some(rule) => {
+                let new_set = rule.target.set.unwrap_or(binding.set);
+                let new_binding = rule.target.binding.unwrap_or(binding.binding);
+                report.remapped.push(RemappedBinding {
+                    name: binding.name.clone(),
+                    set: binding.set,
+                    binding: binding.binding,
+                    new_set,
+                    new_binding,
+                });
+                let moved = (new_set, new_binding) != (binding.set, binding.binding);
+                if moved {
+                    changes.push((binding, new_binding, new_set));
+                }
+                (new_set, new_binding, moved)
+            }
+            None => {
+                report.unmatched.push(UnmatchedBinding {
+                    name: binding.name.clone(),
+                    set: binding.set,
+                    binding: binding.binding,
+                });
+                (binding.set, binding.binding, false)
+            }
+        };
+        slots
+            .entry((new_set, new_binding))
+            .or_default()
+            .push((binding, moved));
+    }
+
+    let collisions: Vec<RemapCollision> = slots
+        .into_iter()
+        .filter(|(_, bindings)| bindings.len() > 1 && bindings.iter().any(|&(_, moved)| moved))
+        .map(|((set, binding), bindings)| RemapCollision {
+            set,
+            binding,
+            names: bindings
+                .iter()
+                .map(|(binding, _)| binding.name.clone())
+                .collect(),
+        })
+        .collect();
+    if !collisions.is_empty() {
+        return Err(RemapError::Collisions(collisions));
+    }
+
+    Ok((report, changes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::descriptor_binding;
+
+    #[test]
+    fn rule_precedence() {
+        let mut table = RemapTable::new();
+        table
+            .by_descriptor_type(ReflectDescriptorType::Sampler, RemapTarget::set(3))
+            .by_set(1, RemapTarget::set(2))
+            .by_binding(1, 4, RemapTarget::new(0, 0))
+            .by_name("shadow", RemapTarget::new(0, 1))
+            .by_set(1, RemapTarget::set(5));
+
+        let target =
+            |binding: &ReflectDescriptorBinding| table.find_rule(binding).map(|rule| rule.target);
+        assert_eq!(
+            target(&descriptor_binding(
+                "linear",
+                0,
+                0,
+                ReflectDescriptorType::Sampler
+            )),
+            Some(RemapTarget::set(3))
+        );
+        assert_eq!(
+            target(&descriptor_binding(
+                "linear",
+                1,
+                0,
+                ReflectDescriptorType::Sampler
+            )),
+            Some(RemapTarget::set(2))
+        );
+        assert_eq!(
+            target(&descriptor_binding(
+                "albedo",
+                1,
+                4,
+                ReflectDescriptorType::SampledImage
+            )),
+            Some(RemapTarget::new(0, 0))
+        );
+        assert_eq!(
+            target(&descriptor_binding(
+                "shadow",
+                1,
+                4,
+                ReflectDescriptorType::Sampler
+            )),
+            Some(RemapTarget::new(0, 1))
+        );
+        assert_eq!(
+            target(&descriptor_binding(
+                "albedo",
+                0,
+                4,
+                ReflectDescriptorType::SampledImage
+            )),
+            None
+        );
+    }
+}
@@ -0,0 +1,21 @@
+use crate::types::{ReflectNumericTraitsScalar, ReflectTypeFlags};
+
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ReflectSpecializationConstantValue {
+    #[default]
+    Undefined,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReflectSpecializationConstant {
+    pub spirv_id: u32,
+    pub constant_id: u32,
+    pub name: String,
+    pub type_flags: ReflectTypeFlags,
+    pub scalar: ReflectNumericTraitsScalar,
+    pub default_value: ReflectSpecializationConstantValue,
+}
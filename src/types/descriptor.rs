@@ -1,11 +1,11 @@
-use crate::ffi;
 use crate::types::{
-    ReflectBindingArrayTraits, ReflectBlockVariable, ReflectImageTraits, ReflectResourceType,
-    ReflectTypeDescription,
+    ReflectBindingArrayTraits, ReflectBlockVariable, ReflectDecorationFlags, ReflectImageTraits,
+    ReflectResourceType, ReflectTypeDescription,
 };
 
-#[derive(Debug, Copy, Clone, Serialize, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ReflectDescriptorType {
+    #[default]
     Undefined,
     Sampler,
     CombinedImageSampler,
@@ -19,19 +19,66 @@ pub enum ReflectDescriptorType {
     StorageBufferDynamic,
     InputAttachment,
     AccelerationStructureKHR,
+    Unknown(u32),
 }
 
-impl Default for ReflectDescriptorType {
-    fn default() -> Self {
-        ReflectDescriptorType::Undefined
-    }
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ReflectUserType {
+    #[default]
+    Invalid,
+    Cbuffer,
+    Tbuffer,
+    AppendStructuredBuffer,
+    Buffer,
+    ByteAddressBuffer,
+    ConstantBuffer,
+    ConsumeStructuredBuffer,
+    InputPatch,
+    OutputPatch,
+    RasterizerOrderedBuffer,
+    RasterizerOrderedByteAddressBuffer,
+    RasterizerOrderedStructuredBuffer,
+    RasterizerOrderedTexture1d,
+    RasterizerOrderedTexture1dArray,
+    RasterizerOrderedTexture2d,
+    RasterizerOrderedTexture2dArray,
+    RasterizerOrderedTexture3d,
+    RaytracingAccelerationStructure,
+    RwBuffer,
+    RwByteAddressBuffer,
+    RwStructuredBuffer,
+    RwTexture1d,
+    RwTexture1dArray,
+    RwTexture2d,
+    RwTexture2dArray,
+    RwTexture3d,
+    StructuredBuffer,
+    SubpassInput,
+    SubpassInputMs,
+    Texture1d,
+    Texture1dArray,
+    Texture2d,
+    Texture2dArray,
+    Texture2dMs,
+    Texture2dMsArray,
+    Texture3d,
+    TextureBuffer,
+    TextureCube,
+    TextureCubeArray,
+    Unknown(u32),
+}
+
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReflectAccess {
+    pub read: bool,
+    pub write: bool,
 }
 
 pub type ReflectOrdinalBinding = u32;
 pub type ReflectOrdinalSet = u32;
 pub type ReflectDescriptorBindingSet = (ReflectOrdinalBinding, ReflectOrdinalSet);
 
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectDescriptorBinding {
     pub spirv_id: u32,
     pub name: String,
@@ -44,18 +91,19 @@ pub struct ReflectDescriptorBinding {
     pub block: ReflectBlockVariable,
     pub array: ReflectBindingArrayTraits,
     pub count: u32,
+    pub accessed: bool,
     pub uav_counter_id: u32,
     pub uav_counter_binding: Option<Box<ReflectDescriptorBinding>>,
+    pub byte_address_buffer_offsets: Vec<u32>,
     pub type_description: Option<ReflectTypeDescription>,
     pub word_offset: ReflectDescriptorBindingSet,
-    #[serde(skip_serializing)]
-    pub(crate) internal_data: *const ffi::SpvReflectDescriptorBinding,
+    pub decoration_flags: ReflectDecorationFlags,
+    pub user_type: ReflectUserType,
+    pub access: ReflectAccess,
 }
 
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectDescriptorSet {
     pub set: u32,
     pub bindings: Vec<ReflectDescriptorBinding>,
-    #[serde(skip_serializing)]
-    pub(crate) internal_data: *const ffi::SpvReflectDescriptorSet,
 }
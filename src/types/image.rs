@@ -1,6 +1,10 @@
+use crate::types::traits::ReflectNumericTraits;
+use crate::types::variable::ReflectTypeFlags;
+
 #[allow(non_camel_case_types)]
-#[derive(Debug, Copy, Clone, Serialize, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ReflectImageFormat {
+    #[default]
     Undefined,
     RGBA32_FLOAT,
     RGBA16_FLOAT,
@@ -41,18 +45,36 @@ pub enum ReflectImageFormat {
     RG8_UINT,
     R16_UINT,
     R8_UINT,
-}
-
-impl Default for ReflectImageFormat {
-    fn default() -> Self {
-        ReflectImageFormat::Undefined
-    }
+    R64_UINT,
+    R64_SINT,
+    Unknown(u32),
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Debug, Copy, Clone, Serialize, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ReflectFormat {
+    #[default]
     Undefined,
+    R8_UINT,
+    R8_SINT,
+    R8G8_UINT,
+    R8G8_SINT,
+    R8G8B8_UINT,
+    R8G8B8_SINT,
+    R8G8B8A8_UINT,
+    R8G8B8A8_SINT,
+    R16_UINT,
+    R16_SINT,
+    R16_SFLOAT,
+    R16G16_UINT,
+    R16G16_SINT,
+    R16G16_SFLOAT,
+    R16G16B16_UINT,
+    R16G16B16_SINT,
+    R16G16B16_SFLOAT,
+    R16G16B16A16_UINT,
+    R16G16B16A16_SINT,
+    R16G16B16A16_SFLOAT,
     R32_UINT,
     R32_SINT,
     R32_SFLOAT,
@@ -65,10 +87,81 @@ pub enum ReflectFormat {
     R32G32B32A32_UINT,
     R32G32B32A32_SINT,
     R32G32B32A32_SFLOAT,
+    R64_UINT,
+    R64_SINT,
+    R64_SFLOAT,
+    R64G64_UINT,
+    R64G64_SINT,
+    R64G64_SFLOAT,
+    R64G64B64_UINT,
+    R64G64B64_SINT,
+    R64G64B64_SFLOAT,
+    R64G64B64A64_UINT,
+    R64G64B64A64_SINT,
+    R64G64B64A64_SFLOAT,
+    Unknown(u32),
 }
 
-impl Default for ReflectFormat {
-    fn default() -> Self {
-        ReflectFormat::Undefined
+impl ReflectFormat {
+    pub fn from_numeric_traits(
+        numeric: &ReflectNumericTraits,
+        type_flags: ReflectTypeFlags,
+    ) -> Self {
+        if numeric.matrix.column_count > 0 {
+            return ReflectFormat::Undefined;
+        }
+        let components = numeric.vector.component_count.max(1);
+        let float = type_flags.contains(ReflectTypeFlags::FLOAT);
+        if !float && !type_flags.contains(ReflectTypeFlags::INT) {
+            return ReflectFormat::Undefined;
+        }
+        let signed = numeric.scalar.signedness != 0;
+        match (numeric.scalar.width, float, signed, components) {
+            (8, false, false, 1) => ReflectFormat::R8_UINT,
+            (8, false, false, 2) => ReflectFormat::R8G8_UINT,
+            (8, false, false, 3) => ReflectFormat::R8G8B8_UINT,
+            (8, false, false, 4) => ReflectFormat::R8G8B8A8_UINT,
+            (8, false, true, 1) => ReflectFormat::R8_SINT,
+            (8, false, true, 2) => ReflectFormat::R8G8_SINT,
+            (8, false, true, 3) => ReflectFormat::R8G8B8_SINT,
+            (8, false, true, 4) => ReflectFormat::R8G8B8A8_SINT,
+            (16, false, false, 1) => ReflectFormat::R16_UINT,
+            (16, false, false, 2) => ReflectFormat::R16G16_UINT,
+            (16, false, false, 3) => ReflectFormat::R16G16B16_UINT,
+            (16, false, false, 4) => ReflectFormat::R16G16B16A16_UINT,
+            (16, false, true, 1) => ReflectFormat::R16_SINT,
+            (16, false, true, 2) => ReflectFormat::R16G16_SINT,
+            (16, false, true, 3) => ReflectFormat::R16G16B16_SINT,
+            (16, false, true, 4) => ReflectFormat::R16G16B16A16_SINT,
+            (16, true, _, 1) => ReflectFormat::R16_SFLOAT,
+            (16, true, _, 2) => ReflectFormat::R16G16_SFLOAT,
+            (16, true, _, 3) => ReflectFormat::R16G16B16_SFLOAT,
+            (16, true, _, 4) => ReflectFormat::R16G16B16A16_SFLOAT,
+            (32, false, false, 1) => ReflectFormat::R32_UINT,
+            (32, false, false, 2) => ReflectFormat::R32G32_UINT,
+            (32, false, false, 3) => ReflectFormat::R32G32B32_UINT,
+            (32, false, false, 4) => ReflectFormat::R32G32B32A32_UINT,
+            (32, false, true, 1) => ReflectFormat::R32_SINT,
+            (32, false, true, 2) => ReflectFormat::R32G32_SINT,
+            (32, false, true, 3) => ReflectFormat::R32G32B32_SINT,
+            (32, false, true, 4) => ReflectFormat::R32G32B32A32_SINT,
+            (32, true, _, 1) => ReflectFormat::R32_SFLOAT,
+            (32, true, _, 2) => ReflectFormat::R32G32_SFLOAT,
+            (32, true, _, 3) => ReflectFormat::R32G32B32_SFLOAT,
+            (32, true, _, 4) => ReflectFormat::R32G32B32A32_SFLOAT,
+            (64, false, false, 1) => ReflectFormat::R64_UINT,
+            (64, false, false, 2) => ReflectFormat::R64G64_UINT,
+            (64, false, false, 3) => ReflectFormat::R64G64B64_UINT,
+            (64, false, false, 4) => ReflectFormat::R64G64B64A64_UINT,
+            (64, false, true, 1) => ReflectFormat::R64_SINT,
+            (64, false, true, 2) => ReflectFormat::R64G64_SINT,
+            (64, false, true, 3) => ReflectFormat::R64G64B64_SINT,
+            (64, false, true, 4) => ReflectFormat::R64G64B64A64_SINT,
+            (64, true, _, 1) => ReflectFormat::R64_SFLOAT,
+            (64, true, _, 2) => ReflectFormat::R64G64_SFLOAT,
+            (64, true, _, 3) => ReflectFormat::R64G64B64_SFLOAT,
+            (64, true, _, 4) => ReflectFormat::R64G64B64A64_SFLOAT,
+            _ => ReflectFormat::Undefined,
+        }
     }
 }
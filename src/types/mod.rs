@@ -1,3 +1,4 @@
+pub mod constant;
 pub mod descriptor;
 pub mod image;
 pub mod op;
@@ -5,6 +6,7 @@ pub mod resource;
 pub mod traits;
 pub mod variable;
 
+pub use self::constant::*;
 pub use self::descriptor::*;
 pub use self::image::*;
 pub use self::op::*;
@@ -12,8 +14,9 @@ pub use self::resource::*;
 pub use self::traits::*;
 pub use self::variable::*;
 
-#[derive(Debug, Copy, Clone, Serialize, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ReflectGenerator {
+    #[default]
     Unknown,
     KhronosLlvmSpirvTranslator,
     KhronosSpirvToolsAssembler,
@@ -27,8 +30,8 @@ pub enum ReflectGenerator {
     ClayClayShaderCompiler,
 }
 
-impl Default for ReflectGenerator {
-    fn default() -> Self {
-        ReflectGenerator::Unknown
-    }
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReflectCapability {
+    pub value: Option<spirv::Capability>,
+    pub word_offset: u32,
 }
@@ -3,7 +3,7 @@ use num_traits::cast::FromPrimitive;
 use spirv;
 use std::ops::Deref;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectOp(spirv::Op);
 
 impl Default for ReflectOp {
@@ -21,14 +21,14 @@ impl Deref for ReflectOp {
 
 impl From<ffi::SpvOp> for ReflectOp {
     fn from(raw_op: ffi::SpvOp) -> Self {
-        match spirv::Op::from_u32(raw_op as u32) {
+        match spirv::Op::from_u32(raw_op) {
             Some(op) => ReflectOp(op),
             None => Default::default(),
         }
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectBuiltIn(spirv::BuiltIn);
 
 impl Default for ReflectBuiltIn {
@@ -46,7 +46,7 @@ impl Deref for ReflectBuiltIn {
 
 impl From<ffi::SpvBuiltIn> for ReflectBuiltIn {
     fn from(raw_built_in: ffi::SpvBuiltIn) -> Self {
-        match spirv::BuiltIn::from_u32(raw_built_in as u32) {
+        match spirv::BuiltIn::from_u32(raw_built_in) {
             Some(built_in) => ReflectBuiltIn(built_in),
             None => Default::default(),
         }
@@ -1,15 +1,11 @@
-#[derive(Debug, Copy, Clone, Serialize, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ReflectResourceType {
+    #[default]
     Undefined,
     Sampler,
     CombinedImageSampler,
     ConstantBufferView,
     ShaderResourceView,
     UnorderedAccessView,
-}
-
-impl Default for ReflectResourceType {
-    fn default() -> Self {
-        ReflectResourceType::Undefined
-    }
+    Unknown(u32),
 }
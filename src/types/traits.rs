@@ -1,50 +1,50 @@
 use crate::types::image::ReflectImageFormat;
 use crate::types::ReflectDimension;
 
-#[derive(Debug, Default, Clone, Serialize, PartialEq)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectBindingArrayTraits {
     pub dims: Vec<u32>,
 }
 
-#[derive(Default, Debug, Copy, Clone, Serialize, PartialEq)]
+#[derive(Default, Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectNumericTraitsScalar {
     pub width: u32,
     pub signedness: u32,
 }
 
-#[derive(Default, Debug, Copy, Clone, Serialize, PartialEq)]
+#[derive(Default, Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectNumericTraitsVector {
     pub component_count: u32,
 }
 
-#[derive(Default, Debug, Copy, Clone, Serialize, PartialEq)]
+#[derive(Default, Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectNumericTraitsMatrix {
     pub column_count: u32,
     pub row_count: u32,
     pub stride: u32,
 }
 
-#[derive(Default, Debug, Copy, Clone, Serialize, PartialEq)]
+#[derive(Default, Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectNumericTraits {
     pub scalar: ReflectNumericTraitsScalar,
     pub vector: ReflectNumericTraitsVector,
     pub matrix: ReflectNumericTraitsMatrix,
 }
 
-#[derive(Default, Debug, Clone, Serialize, PartialEq)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectArrayTraits {
     pub dims: Vec<u32>,
     pub stride: u32,
 }
 
-#[derive(Default, Debug, Clone, Serialize, PartialEq)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectTypeDescriptionTraits {
     pub numeric: ReflectNumericTraits,
     pub image: ReflectImageTraits,
     pub array: ReflectArrayTraits,
 }
 
-#[derive(Default, Debug, Copy, Clone, Serialize, PartialEq)]
+#[derive(Default, Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectImageTraits {
     pub dim: ReflectDimension,
     pub depth: u32,
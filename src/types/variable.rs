@@ -1,11 +1,10 @@
-use crate::ffi;
 use crate::types::descriptor::ReflectDescriptorSet;
 use crate::types::image::ReflectFormat;
 use crate::types::op::{ReflectBuiltIn, ReflectOp};
 use crate::types::traits::*;
 
 bitflags! {
-    #[derive(Serialize)]
+    #[derive(Serialize, Deserialize)]
     pub struct ReflectDecorationFlags: u32 {
         const NONE = 0;
         const BLOCK = 1;
@@ -33,7 +32,22 @@ impl Default for ReflectDecorationFlags {
 }
 
 bitflags! {
-    #[derive(Serialize)]
+    #[derive(Serialize, Deserialize)]
+    pub struct ReflectVariableFlags: u32 {
+        const NONE = 0;
+        const UNUSED = 1;
+        const PHYSICAL_POINTER_COPY = 2;
+    }
+}
+
+impl Default for ReflectVariableFlags {
+    fn default() -> Self {
+        ReflectVariableFlags::NONE
+    }
+}
+
+bitflags! {
+    #[derive(Serialize, Deserialize)]
     pub struct ReflectTypeFlags: u32 {
         const UNDEFINED = 0;
         const VOID = 1;
@@ -61,7 +75,7 @@ impl Default for ReflectTypeFlags {
 }
 
 bitflags! {
-    #[derive(Serialize)]
+    #[derive(Serialize, Deserialize)]
     pub struct ReflectShaderStageFlags: u32 {
         const UNDEFINED = 0x0000_0000;
         const VERTEX = 0x0000_0001;
@@ -89,8 +103,9 @@ impl Default for ReflectShaderStageFlags {
     }
 }
 
-#[derive(Debug, Copy, Clone, Serialize, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ReflectDimension {
+    #[default]
     Undefined,
     Type1d,
     Type2d,
@@ -99,19 +114,14 @@ pub enum ReflectDimension {
     Rect,
     Buffer,
     SubPassData,
+    TileImageDataEXT,
+    Unknown(u32),
 }
 
-impl Default for ReflectDimension {
-    fn default() -> Self {
-        ReflectDimension::Undefined
-    }
-}
-
-#[derive(Default, Debug, Clone, Serialize, PartialEq)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectTypeDescription {
     pub id: u32,
-    #[serde(skip_serializing)]
-    pub op: ReflectOp, // TODO: Serialization support
+    pub op: ReflectOp,
     pub type_name: String,
     pub struct_member_name: String,
     pub storage_class: ReflectStorageClass,
@@ -121,7 +131,7 @@ pub struct ReflectTypeDescription {
     pub members: Vec<ReflectTypeDescription>,
 }
 
-#[derive(Default, Debug, Clone, Serialize, PartialEq)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectBlockVariable {
     pub spirv_id: u32,
     pub name: String,
@@ -132,12 +142,14 @@ pub struct ReflectBlockVariable {
     pub decoration_flags: ReflectDecorationFlags,
     pub numeric: ReflectNumericTraits,
     pub array: ReflectArrayTraits,
+    pub flags: ReflectVariableFlags,
     pub members: Vec<ReflectBlockVariable>,
     pub type_description: Option<ReflectTypeDescription>,
 }
 
-#[derive(Debug, Copy, Clone, Serialize, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ReflectStorageClass {
+    #[default]
     Undefined,
     UniformConstant,
     Input,
@@ -152,47 +164,124 @@ pub enum ReflectStorageClass {
     AtomicCounter,
     Image,
     StorageBuffer,
+    TileImageEXT,
+    CallableDataKHR,
+    IncomingCallableDataKHR,
+    RayPayloadKHR,
+    HitAttributeKHR,
+    IncomingRayPayloadKHR,
+    ShaderRecordBufferKHR,
+    PhysicalStorageBuffer,
+    HitObjectAttributeNV,
+    TaskPayloadWorkgroupEXT,
+    CodeSectionINTEL,
+    DeviceOnlyINTEL,
+    HostOnlyINTEL,
+    Unknown(u32),
 }
 
-impl Default for ReflectStorageClass {
-    fn default() -> Self {
-        ReflectStorageClass::Undefined
-    }
-}
-
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectInterfaceVariable {
     pub spirv_id: u32,
     pub name: String,
     pub location: u32,
+    pub component: u32,
     pub storage_class: ReflectStorageClass,
     pub semantic: String,
     pub decoration_flags: ReflectDecorationFlags,
-    #[serde(skip_serializing)]
-    pub built_in: ReflectBuiltIn, // TODO: Serialization support
+    pub built_in: ReflectBuiltIn,
     pub numeric: ReflectNumericTraits,
     pub array: ReflectArrayTraits,
     pub members: Vec<ReflectInterfaceVariable>,
     pub format: ReflectFormat,
     pub type_description: Option<ReflectTypeDescription>,
     pub word_offset: u32,
-    #[serde(skip_serializing)]
-    pub(crate) internal_data: *const ffi::SpvReflectInterfaceVariable,
 }
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectEntryPointLocalSize {
     pub x: u32,
     pub y: u32,
     pub z: u32,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReflectEntryPointLocalSizeId {
+    pub x_id: u32,
+    pub y_id: u32,
+    pub z_id: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ReflectExecutionMode {
+    Invocations(u32),
+    SpacingEqual,
+    SpacingFractionalEven,
+    SpacingFractionalOdd,
+    VertexOrderCw,
+    VertexOrderCcw,
+    PixelCenterInteger,
+    OriginUpperLeft,
+    OriginLowerLeft,
+    EarlyFragmentTests,
+    PointMode,
+    Xfb,
+    DepthReplacing,
+    DepthGreater,
+    DepthLess,
+    DepthUnchanged,
+    LocalSize(ReflectEntryPointLocalSize),
+    LocalSizeHint(ReflectEntryPointLocalSize),
+    InputPoints,
+    InputLines,
+    InputLinesAdjacency,
+    Triangles,
+    InputTrianglesAdjacency,
+    Quads,
+    Isolines,
+    OutputVertices(u32),
+    OutputPoints,
+    OutputLineStrip,
+    OutputTriangleStrip,
+    VecTypeHint(u32),
+    ContractionOff,
+    Initializer,
+    Finalizer,
+    SubgroupSize(u32),
+    SubgroupsPerWorkgroup(u32),
+    SubgroupsPerWorkgroupId(u32),
+    LocalSizeId(ReflectEntryPointLocalSizeId),
+    LocalSizeHintId(ReflectEntryPointLocalSizeId),
+    PostDepthCoverage,
+    DenormPreserve(u32),
+    DenormFlushToZero(u32),
+    SignedZeroInfNanPreserve(u32),
+    RoundingModeRTE(u32),
+    RoundingModeRTZ(u32),
+    StencilRefReplacingEXT,
+    OutputLinesNV,
+    OutputPrimitivesNV(u32),
+    DerivativeGroupQuadsNV,
+    DerivativeGroupLinearNV,
+    OutputTrianglesNV,
+    PixelInterlockOrderedEXT,
+    PixelInterlockUnorderedEXT,
+    SampleInterlockOrderedEXT,
+    SampleInterlockUnorderedEXT,
+    ShadingRateInterlockOrderedEXT,
+    ShadingRateInterlockUnorderedEXT,
+    MaxWorkgroupSizeINTEL(ReflectEntryPointLocalSize),
+    MaxWorkDimINTEL(u32),
+    NoGlobalOffsetINTEL,
+    NumSIMDWorkitemsINTEL(u32),
+    Unknown { mode: u32, operands: Vec<u32> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectEntryPoint {
     pub name: String,
     pub id: u32,
-    #[serde(skip_serializing)]
-    pub spirv_execution_model: spirv::ExecutionModel, // TODO: Serialization support
+    pub spirv_execution_model: spirv::ExecutionModel,
     pub shader_stage: ReflectShaderStageFlags,
     pub input_variables: Vec<ReflectInterfaceVariable>,
     pub output_variables: Vec<ReflectInterfaceVariable>,
@@ -200,4 +289,7 @@ pub struct ReflectEntryPoint {
     pub used_uniforms: Vec<u32>,
     pub used_push_constants: Vec<u32>,
     pub local_size: ReflectEntryPointLocalSize,
+    pub invocations: u32,
+    pub output_vertices: u32,
+    pub execution_modes: Vec<ReflectExecutionMode>,
 }
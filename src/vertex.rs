@@ -0,0 +1,247 @@
+use crate::types::{
+    ReflectDecorationFlags, ReflectFormat, ReflectInterfaceVariable, ReflectNumericTraits,
+    ReflectNumericTraitsMatrix, ReflectNumericTraitsVector,
+};
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum VertexBufferStrategy {
+    Interleaved,
+    PerLocation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VertexAttribute {
+    pub name: String,
+    pub location: u32,
+    pub binding: u32,
+    pub format: ReflectFormat,
+    pub offset: u32,
+    pub size: u32,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VertexBufferLayout {
+    pub binding: u32,
+    pub stride: u32,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VertexInputLayout {
+    pub attributes: Vec<VertexAttribute>,
+    pub buffers: Vec<VertexBufferLayout>,
+}
+
+pub fn derive_vertex_input_layout(
+    variables: &[ReflectInterfaceVariable],
+    strategy: VertexBufferStrategy,
+) -> VertexInputLayout {
+    let mut variables: Vec<&ReflectInterfaceVariable> = variables
+        .iter()
+        .filter(|variable| {
+            // Inputs without a Location decoration report `u32::MAX` and can't be bound.
+            variable.location != u32::MAX
+                && !variable
+                    .decoration_flags
+                    .contains(ReflectDecorationFlags::BUILT_IN)
+        })
+        .collect();
+    variables.sort_by_key(|variable| variable.location);
+
+    let mut layout = VertexInputLayout::default();
+    let mut offset = 0;
+    for variable in variables {
+        for (location, format, size) in variable_attributes(variable) {
+            let attribute = match strategy {
+                VertexBufferStrategy::Interleaved => {
+                    let attribute_offset = offset;
+                    offset += size;
+                    VertexAttribute {
+                        name: variable.name.clone(),
+                        location,
+                        binding: 0,
+                        format,
+                        offset: attribute_offset,
+                        size,
+                    }
+                }
+                VertexBufferStrategy::PerLocation => {
+                    layout.buffers.push(VertexBufferLayout {
+                        binding: location,
+                        stride: size,
+                    });
+                    VertexAttribute {
+                        name: variable.name.clone(),
+                        location,
+                        binding: location,
+                        format,
+                        offset: 0,
+                        size,
+                    }
+                }
+            };
+            layout.attributes.push(attribute);
+        }
+    }
+
+    if strategy == VertexBufferStrategy::Interleaved && !layout.attributes.is_empty() {
+        layout.buffers.push(VertexBufferLayout {
+            binding: 0,
+            stride: offset,
+        });
+    }
+
+    layout
+}
+
+// Matrices take one location per column and arrays one per element. 64-bit vectors with
+// more than two components take two locations each.
+fn variable_attributes(variable: &ReflectInterfaceVariable) -> Vec<(u32, ReflectFormat, u32)> {
+    let numeric = &variable.numeric;
+    let is_matrix = numeric.matrix.column_count > 0;
+    let (columns, components) = if is_matrix {
+        (numeric.matrix.column_count, numeric.matrix.row_count)
+    } else {
+        (1, numeric.vector.component_count.max(1))
+    };
+    let elements: u32 = variable.array.dims.iter().product();
+
+    let format = if !is_matrix && variable.format != ReflectFormat::Undefined {
+        variable.format
+    } else {
+        let type_flags = variable
+            .type_description
+            .as_ref()
+            .map(|type_description| type_description.type_flags)
+            .unwrap_or_default();
+        let element = ReflectNumericTraits {
+            scalar: numeric.scalar,
+            vector: ReflectNumericTraitsVector {
+                component_count: components,
+            },
+            matrix: ReflectNumericTraitsMatrix::default(),
+        };
+        ReflectFormat::from_numeric_traits(&element, type_flags)
+    };
+    let size = numeric.scalar.width.max(8) / 8 * components;
+    let location_count = if numeric.scalar.width > 32 && components > 2 {
+        2
+    } else {
+        1
+    };
+
+    let mut location = Some(variable.location);
+    let mut attributes = Vec::new();
+    for _ in 0..elements * columns {
+        match location {
+            Some(current) => {
+                attributes.push((current, format, size));
+                location = current.checked_add(location_count);
+            }
+            None => break,
+        }
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{float, interface_variable};
+    use crate::types::*;
+
+    #[test]
+    fn matrix_and_array_locations() {
+        let mut transform = interface_variable(
+            "transform",
+            0,
+            ReflectFormat::Undefined,
+            ReflectNumericTraits {
+                matrix: ReflectNumericTraitsMatrix {
+                    column_count: 4,
+                    row_count: 4,
+                    stride: 16,
+                },
+                ..float(4)
+            },
+        );
+        transform.array.dims = vec![2];
+        let weights = interface_variable("weights", 8, ReflectFormat::Undefined, float(2));
+        let mut position = interface_variable(
+            "position",
+            u32::MAX,
+            ReflectFormat::Undefined,
+            weights.numeric,
+        );
+        position.decoration_flags = ReflectDecorationFlags::BUILT_IN;
+
+        let variables = vec![weights, position, transform];
+        let layout = derive_vertex_input_layout(&variables, VertexBufferStrategy::Interleaved);
+        let locations: Vec<(u32, u32)> = layout
+            .attributes
+            .iter()
+            .map(|attribute| (attribute.location, attribute.offset))
+            .collect();
+        assert_eq!(
+            locations,
+            vec![
+                (0, 0),
+                (1, 16),
+                (2, 32),
+                (3, 48),
+                (4, 64),
+                (5, 80),
+                (6, 96),
+                (7, 112),
+                (8, 128)
+            ]
+        );
+        assert!(layout.attributes[..8]
+            .iter()
+            .all(|attribute| attribute.format == ReflectFormat::R32G32B32A32_SFLOAT));
+        assert_eq!(layout.attributes[8].format, ReflectFormat::R32G32_SFLOAT);
+        assert_eq!(
+            layout.buffers,
+            vec![VertexBufferLayout {
+                binding: 0,
+                stride: 136
+            }]
+        );
+
+        let layout = derive_vertex_input_layout(&variables, VertexBufferStrategy::PerLocation);
+        assert_eq!(layout.attributes.len(), 9);
+        assert!(layout
+            .attributes
+            .iter()
+            .all(|attribute| attribute.offset == 0 && attribute.binding == attribute.location));
+        assert_eq!(
+            layout.buffers[8],
+            VertexBufferLayout {
+                binding: 8,
+                stride: 8
+            }
+        );
+    }
+
+    #[test]
+    fn inputs_without_locations() {
+        let mut transform =
+            interface_variable("transform", u32::MAX, ReflectFormat::Undefined, float(4));
+        transform.numeric.matrix = ReflectNumericTraitsMatrix {
+            column_count: 4,
+            row_count: 4,
+            stride: 16,
+        };
+        let mut last = transform.clone();
+        last.location = u32::MAX - 1;
+        let uv = interface_variable("uv", 0, ReflectFormat::Undefined, float(2));
+
+        let layout =
+            derive_vertex_input_layout(&[transform, last, uv], VertexBufferStrategy::Interleaved);
+        let locations: Vec<u32> = layout
+            .attributes
+            .iter()
+            .map(|attribute| attribute.location)
+            .collect();
+        assert_eq!(locations, vec![0, u32::MAX - 1, u32::MAX]);
+    }
+}
@@ -0,0 +1,271 @@
+use crate::pipeline::{PipelineLayoutPushConstantRange, PipelineLayoutSet};
+use crate::types::{
+    ReflectBlockVariable, ReflectDescriptorBinding, ReflectDescriptorSet, ReflectDescriptorType,
+    ReflectFormat, ReflectShaderStageFlags,
+};
+use ash::vk;
+use std::convert::TryFrom;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DescriptorSetLayoutBindingErrorKind {
+    UndefinedDescriptorType,
+    RuntimeArray,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DescriptorSetLayoutBindingError {
+    pub set: u32,
+    pub binding: u32,
+    pub name: String,
+    pub kind: DescriptorSetLayoutBindingErrorKind,
+}
+
+impl fmt::Display for DescriptorSetLayoutBindingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "set {} binding {} ({}): ",
+            self.set, self.binding, self.name
+        )?;
+        match &self.kind {
+            DescriptorSetLayoutBindingErrorKind::UndefinedDescriptorType => {
+                write!(f, "descriptor type is undefined")
+            }
+            DescriptorSetLayoutBindingErrorKind::RuntimeArray => {
+                write!(f, "runtime arrays need an explicit descriptor count")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DescriptorSetLayoutBindingError {}
+
+impl From<ReflectShaderStageFlags> for vk::ShaderStageFlags {
+    fn from(stage_flags: ReflectShaderStageFlags) -> Self {
+        vk::ShaderStageFlags::from_raw(stage_flags.bits())
+    }
+}
+
+impl TryFrom<ReflectDescriptorType> for vk::DescriptorType {
+    type Error = ReflectDescriptorType;
+
+    fn try_from(descriptor_type: ReflectDescriptorType) -> Result<Self, Self::Error> {
+        Ok(match descriptor_type {
+            ReflectDescriptorType::Undefined => return Err(descriptor_type),
+            ReflectDescriptorType::Sampler => vk::DescriptorType::SAMPLER,
+            ReflectDescriptorType::CombinedImageSampler => {
+                vk::DescriptorType::COMBINED_IMAGE_SAMPLER
+            }
+            ReflectDescriptorType::SampledImage => vk::DescriptorType::SAMPLED_IMAGE,
+            ReflectDescriptorType::StorageImage => vk::DescriptorType::STORAGE_IMAGE,
+            ReflectDescriptorType::UniformTexelBuffer => vk::DescriptorType::UNIFORM_TEXEL_BUFFER,
+            ReflectDescriptorType::StorageTexelBuffer => vk::DescriptorType::STORAGE_TEXEL_BUFFER,
+            ReflectDescriptorType::UniformBuffer => vk::DescriptorType::UNIFORM_BUFFER,
+            ReflectDescriptorType::StorageBuffer => vk::DescriptorType::STORAGE_BUFFER,
+            ReflectDescriptorType::UniformBufferDynamic => {
+                vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC
+            }
+            ReflectDescriptorType::StorageBufferDynamic => {
+                vk::DescriptorType::STORAGE_BUFFER_DYNAMIC
+            }
+            ReflectDescriptorType::InputAttachment => vk::DescriptorType::INPUT_ATTACHMENT,
+            ReflectDescriptorType::AccelerationStructureKHR => {
+                vk::DescriptorType::ACCELERATION_STRUCTURE_KHR
+            }
+            // Unknown values share Vulkan's numbering, like newer extension types.
+            ReflectDescriptorType::Unknown(raw) => vk::DescriptorType::from_raw(raw as i32),
+        })
+    }
+}
+
+impl From<ReflectFormat> for vk::Format {
+    fn from(format: ReflectFormat) -> Self {
+        match format {
+            ReflectFormat::Undefined => vk::Format::UNDEFINED,
+            ReflectFormat::R8_UINT => vk::Format::R8_UINT,
+            ReflectFormat::R8_SINT => vk::Format::R8_SINT,
+            ReflectFormat::R8G8_UINT => vk::Format::R8G8_UINT,
+            ReflectFormat::R8G8_SINT => vk::Format::R8G8_SINT,
+            ReflectFormat::R8G8B8_UINT => vk::Format::R8G8B8_UINT,
+            ReflectFormat::R8G8B8_SINT => vk::Format::R8G8B8_SINT,
+            ReflectFormat::R8G8B8A8_UINT => vk::Format::R8G8B8A8_UINT,
+            ReflectFormat::R8G8B8A8_SINT => vk::Format::R8G8B8A8_SINT,
+            ReflectFormat::R16_UINT => vk::Format::R16_UINT,
+            ReflectFormat::R16_SINT => vk::Format::R16_SINT,
+            ReflectFormat::R16_SFLOAT => vk::Format::R16_SFLOAT,
+            ReflectFormat::R16G16_UINT => vk::Format::R16G16_UINT,
+            ReflectFormat::R16G16_SINT => vk::Format::R16G16_SINT,
+            ReflectFormat::R16G16_SFLOAT => vk::Format::R16G16_SFLOAT,
+            ReflectFormat::R16G16B16_UINT => vk::Format::R16G16B16_UINT,
+            ReflectFormat::R16G16B16_SINT => vk::Format::R16G16B16_SINT,
+            ReflectFormat::R16G16B16_SFLOAT => vk::Format::R16G16B16_SFLOAT,
+            ReflectFormat::R16G16B16A16_UINT => vk::Format::R16G16B16A16_UINT,
+            ReflectFormat::R16G16B16A16_SINT => vk::Format::R16G16B16A16_SINT,
+            ReflectFormat::R16G16B16A16_SFLOAT => vk::Format::R16G16B16A16_SFLOAT,
+            ReflectFormat::R32_UINT => vk::Format::R32_UINT,
+            ReflectFormat::R32_SINT => vk::Format::R32_SINT,
+            ReflectFormat::R32_SFLOAT => vk::Format::R32_SFLOAT,
+            ReflectFormat::R32G32_UINT => vk::Format::R32G32_UINT,
+            ReflectFormat::R32G32_SINT => vk::Format::R32G32_SINT,
+            ReflectFormat::R32G32_SFLOAT => vk::Format::R32G32_SFLOAT,
+            ReflectFormat::R32G32B32_UINT => vk::Format::R32G32B32_UINT,
+            ReflectFormat::R32G32B32_SINT => vk::Format::R32G32B32_SINT,
+            ReflectFormat::R32G32B32_SFLOAT => vk::Format::R32G32B32_SFLOAT,
+            ReflectFormat::R32G32B32A32_UINT => vk::Format::R32G32B32A32_UINT,
+            ReflectFormat::R32G32B32A32_SINT => vk::Format::R32G32B32A32_SINT,
+            ReflectFormat::R32G32B32A32_SFLOAT => vk::Format::R32G32B32A32_SFLOAT,
+            ReflectFormat::R64_UINT => vk::Format::R64_UINT,
+            ReflectFormat::R64_SINT => vk::Format::R64_SINT,
+            ReflectFormat::R64_SFLOAT => vk::Format::R64_SFLOAT,
+            ReflectFormat::R64G64_UINT => vk::Format::R64G64_UINT,
+            ReflectFormat::R64G64_SINT => vk::Format::R64G64_SINT,
+            ReflectFormat::R64G64_SFLOAT => vk::Format::R64G64_SFLOAT,
+            ReflectFormat::R64G64B64_UINT => vk::Format::R64G64B64_UINT,
+            ReflectFormat::R64G64B64_SINT => vk::Format::R64G64B64_SINT,
+            ReflectFormat::R64G64B64_SFLOAT => vk::Format::R64G64B64_SFLOAT,
+            ReflectFormat::R64G64B64A64_UINT => vk::Format::R64G64B64A64_UINT,
+            ReflectFormat::R64G64B64A64_SINT => vk::Format::R64G64B64A64_SINT,
+            ReflectFormat::R64G64B64A64_SFLOAT => vk::Format::R64G64B64A64_SFLOAT,
+            ReflectFormat::Unknown(raw) => vk::Format::from_raw(raw as i32),
+        }
+    }
+}
+
+impl From<PipelineLayoutPushConstantRange> for vk::PushConstantRange {
+    fn from(range: PipelineLayoutPushConstantRange) -> Self {
+        vk::PushConstantRange {
+            stage_flags: range.stage_flags.into(),
+            offset: range.offset,
+            size: range.size,
+        }
+    }
+}
+
+pub fn descriptor_set_layout_binding(
+    binding: &ReflectDescriptorBinding,
+    stage_flags: ReflectShaderStageFlags,
+) -> Result<vk::DescriptorSetLayoutBinding<'static>, DescriptorSetLayoutBindingError> {
+    let error = |kind| DescriptorSetLayoutBindingError {
+        set: binding.set,
+        binding: binding.binding,
+        name: binding.name.clone(),
+        kind,
+    };
+    let descriptor_type = vk::DescriptorType::try_from(binding.descriptor_type)
+        .map_err(|_| error(DescriptorSetLayoutBindingErrorKind::UndefinedDescriptorType))?;
+    if binding.count == 0 {
+        return Err(error(DescriptorSetLayoutBindingErrorKind::RuntimeArray));
+    }
+    Ok(vk::DescriptorSetLayoutBinding::default()
+        .binding(binding.binding)
+        .descriptor_type(descriptor_type)
+        .descriptor_count(binding.count)
+        .stage_flags(stage_flags.into()))
+}
+
+pub fn descriptor_set_layout_bindings(
+    set: &ReflectDescriptorSet,
+    stage_flags: ReflectShaderStageFlags,
+) -> Result<Vec<vk::DescriptorSetLayoutBinding<'static>>, DescriptorSetLayoutBindingError> {
+    set.bindings
+        .iter()
+        .map(|binding| descriptor_set_layout_binding(binding, stage_flags))
+        .collect()
+}
+
+// Uses the stages merged by `PipelineLayoutBuilder` for each binding.
+pub fn pipeline_layout_set_bindings(
+    set: &PipelineLayoutSet,
+) -> Result<Vec<vk::DescriptorSetLayoutBinding<'static>>, DescriptorSetLayoutBindingError> {
+    set.bindings
+        .iter()
+        .map(|binding| descriptor_set_layout_binding(&binding.binding, binding.stage_flags))
+        .collect()
+}
+
+pub fn push_constant_range(
+    block: &ReflectBlockVariable,
+    stage_flags: ReflectShaderStageFlags,
+) -> vk::PushConstantRange {
+    vk::PushConstantRange {
+        stage_flags: stage_flags.into(),
+        offset: block.offset,
+        size: block.size,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::descriptor_binding;
+
+    #[test]
+    fn raw_values_match() {
+        for bits in 0..14 {
+            let stage = ReflectShaderStageFlags::from_bits_truncate(1 << bits);
+            assert_eq!(vk::ShaderStageFlags::from(stage).as_raw(), stage.bits());
+        }
+        assert_eq!(
+            vk::ShaderStageFlags::from(
+                ReflectShaderStageFlags::VERTEX | ReflectShaderStageFlags::FRAGMENT
+            ),
+            vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT
+        );
+        assert_eq!(
+            vk::ShaderStageFlags::from(ReflectShaderStageFlags::CALLABLE_BIT_NV),
+            vk::ShaderStageFlags::CALLABLE_KHR
+        );
+
+        assert_eq!(
+            vk::DescriptorType::try_from(ReflectDescriptorType::StorageBufferDynamic),
+            Ok(vk::DescriptorType::STORAGE_BUFFER_DYNAMIC)
+        );
+        assert_eq!(
+            vk::DescriptorType::try_from(ReflectDescriptorType::AccelerationStructureKHR)
+                .map(vk::DescriptorType::as_raw),
+            Ok(1_000_150_000)
+        );
+        assert_eq!(
+            vk::DescriptorType::try_from(ReflectDescriptorType::Undefined),
+            Err(ReflectDescriptorType::Undefined)
+        );
+        assert_eq!(
+            vk::Format::from(ReflectFormat::R32G32B32_SFLOAT),
+            vk::Format::R32G32B32_SFLOAT
+        );
+        assert_eq!(
+            vk::Format::from(ReflectFormat::Unknown(1234)).as_raw(),
+            1234
+        );
+    }
+
+    #[test]
+    fn unsupported_bindings() {
+        let mut binding = descriptor_binding("textures", 1, 2, ReflectDescriptorType::SampledImage);
+        binding.array.dims = vec![0];
+        let err =
+            descriptor_set_layout_binding(&binding, ReflectShaderStageFlags::FRAGMENT).unwrap_err();
+        assert_eq!(err.kind, DescriptorSetLayoutBindingErrorKind::RuntimeArray);
+        assert_eq!(
+            err.to_string(),
+            "set 1 binding 2 (textures): runtime arrays need an explicit descriptor count"
+        );
+
+        binding.count = 8;
+        assert_eq!(
+            descriptor_set_layout_binding(&binding, ReflectShaderStageFlags::FRAGMENT)
+                .unwrap()
+                .descriptor_count,
+            8
+        );
+
+        binding.descriptor_type = ReflectDescriptorType::Undefined;
+        let err =
+            descriptor_set_layout_binding(&binding, ReflectShaderStageFlags::FRAGMENT).unwrap_err();
+        assert_eq!(
+            err.kind,
+            DescriptorSetLayoutBindingErrorKind::UndefinedDescriptorType
+        );
+    }
+}
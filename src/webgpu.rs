@@ -0,0 +1,451 @@
+use crate::pipeline::PipelineLayoutSet;
+use crate::types::{
+    ReflectDescriptorBinding, ReflectDescriptorSet, ReflectDescriptorType, ReflectDimension,
+    ReflectImageFormat, ReflectShaderStageFlags, ReflectTypeFlags,
+};
+use std::fmt;
+use std::num::{NonZeroU32, NonZeroU64};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindGroupLayoutErrorKind {
+    UnsupportedStage(ReflectShaderStageFlags),
+    UnsupportedDescriptorType(ReflectDescriptorType),
+    UnsupportedDimension {
+        dim: ReflectDimension,
+        arrayed: bool,
+    },
+    UnsupportedStorageFormat(ReflectImageFormat),
+    MultisampledStorageTexture,
+    RuntimeArray,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindGroupLayoutError {
+    pub set: u32,
+    pub binding: u32,
+    pub name: String,
+    pub kind: BindGroupLayoutErrorKind,
+}
+
+impl fmt::Display for BindGroupLayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "set {} binding {} ({}): ",
+            self.set, self.binding, self.name
+        )?;
+        match &self.kind {
+            BindGroupLayoutErrorKind::UnsupportedStage(stage) => {
+                write!(f, "wgpu has no shader stage for {:?}", stage)
+            }
+            BindGroupLayoutErrorKind::UnsupportedDescriptorType(descriptor_type) => {
+                write!(f, "wgpu has no binding type for {:?}", descriptor_type)
+            }
+            BindGroupLayoutErrorKind::UnsupportedDimension { dim, arrayed } => write!(
+                f,
+                "wgpu has no texture view dimension for {}{:?}",
+                if *arrayed { "arrayed " } else { "" },
+                dim
+            ),
+            BindGroupLayoutErrorKind::UnsupportedStorageFormat(format) => {
+                write!(f, "wgpu has no storage texture format for {:?}", format)
+            }
+            BindGroupLayoutErrorKind::MultisampledStorageTexture => {
+                write!(f, "wgpu does not support multisampled storage textures")
+            }
+            BindGroupLayoutErrorKind::RuntimeArray => {
+                write!(f, "wgpu binding arrays need a fixed size")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BindGroupLayoutError {}
+
+pub fn shader_stages(
+    stage_flags: ReflectShaderStageFlags,
+) -> Result<wgpu::ShaderStages, ReflectShaderStageFlags> {
+    let mapping = [
+        (ReflectShaderStageFlags::VERTEX, wgpu::ShaderStages::VERTEX),
+        (
+            ReflectShaderStageFlags::FRAGMENT,
+            wgpu::ShaderStages::FRAGMENT,
+        ),
+        (
+            ReflectShaderStageFlags::COMPUTE,
+            wgpu::ShaderStages::COMPUTE,
+        ),
+        (ReflectShaderStageFlags::TASK_EXT, wgpu::ShaderStages::TASK),
+        (ReflectShaderStageFlags::MESH_EXT, wgpu::ShaderStages::MESH),
+        (
+            ReflectShaderStageFlags::RAYGEN_BIT_NV,
+            wgpu::ShaderStages::RAY_GENERATION,
+        ),
+        (
+            ReflectShaderStageFlags::ANY_HIT_BIT_NV,
+            wgpu::ShaderStages::ANY_HIT,
+        ),
+        (
+            ReflectShaderStageFlags::CLOSEST_HIT_BIT_NV,
+            wgpu::ShaderStages::CLOSEST_HIT,
+        ),
+        (
+            ReflectShaderStageFlags::MISS_BIT_NV,
+            wgpu::ShaderStages::MISS,
+        ),
+    ];
+    let mut remaining = stage_flags;
+    let mut stages = wgpu::ShaderStages::NONE;
+    for &(reflect_stage, wgpu_stage) in &mapping {
+        if stage_flags.contains(reflect_stage) {
+            stages |= wgpu_stage;
+            remaining.remove(reflect_stage);
+        }
+    }
+    if remaining.is_empty() {
+        Ok(stages)
+    } else {
+        Err(remaining)
+    }
+}
+
+fn storage_format(format: ReflectImageFormat) -> Option<wgpu::TextureFormat> {
+    use wgpu::TextureFormat;
+    let format = match format {
+        ReflectImageFormat::RGBA32_FLOAT => TextureFormat::Rgba32Float,
+        ReflectImageFormat::RGBA16_FLOAT => TextureFormat::Rgba16Float,
+        ReflectImageFormat::R32_FLOAT => TextureFormat::R32Float,
+        ReflectImageFormat::RGBA8 => TextureFormat::Rgba8Unorm,
+        ReflectImageFormat::RGBA8_SNORM => TextureFormat::Rgba8Snorm,
+        ReflectImageFormat::RG32_FLOAT => TextureFormat::Rg32Float,
+        ReflectImageFormat::RG16_FLOAT => TextureFormat::Rg16Float,
+        ReflectImageFormat::R11G11B10_FLOAT => TextureFormat::Rg11b10Ufloat,
+        ReflectImageFormat::R16_FLOAT => TextureFormat::R16Float,
+        ReflectImageFormat::RGBA16 => TextureFormat::Rgba16Unorm,
+        ReflectImageFormat::RGB10A2 => TextureFormat::Rgb10a2Unorm,
+        ReflectImageFormat::RG16 => TextureFormat::Rg16Unorm,
+        ReflectImageFormat::RG8 => TextureFormat::Rg8Unorm,
+        ReflectImageFormat::R16 => TextureFormat::R16Unorm,
+        ReflectImageFormat::R8 => TextureFormat::R8Unorm,
+        ReflectImageFormat::RGBA16_SNORM => TextureFormat::Rgba16Snorm,
+        ReflectImageFormat::RG16_SNORM => TextureFormat::Rg16Snorm,
+        ReflectImageFormat::RG8_SNORM => TextureFormat::Rg8Snorm,
+        ReflectImageFormat::R16_SNORM => TextureFormat::R16Snorm,
+        ReflectImageFormat::R8_SNORM => TextureFormat::R8Snorm,
+        ReflectImageFormat::RGBA32_INT => TextureFormat::Rgba32Sint,
+        ReflectImageFormat::RGBA16_INT => TextureFormat::Rgba16Sint,
+        ReflectImageFormat::RGBA8_INT => TextureFormat::Rgba8Sint,
+        ReflectImageFormat::R32_INT => TextureFormat::R32Sint,
+        ReflectImageFormat::RG32_INT => TextureFormat::Rg32Sint,
+        ReflectImageFormat::RG16_INT => TextureFormat::Rg16Sint,
+        ReflectImageFormat::RG8_INT => TextureFormat::Rg8Sint,
+        ReflectImageFormat::R16_INT => TextureFormat::R16Sint,
+        ReflectImageFormat::R8_INT => TextureFormat::R8Sint,
+        ReflectImageFormat::RGBA32_UINT => TextureFormat::Rgba32Uint,
+        ReflectImageFormat::RGBA16_UINT => TextureFormat::Rgba16Uint,
+        ReflectImageFormat::RGBA8_UINT => TextureFormat::Rgba8Uint,
+        ReflectImageFormat::R32_UINT => TextureFormat::R32Uint,
+        ReflectImageFormat::RGB10A2_UINT => TextureFormat::Rgb10a2Uint,
+        ReflectImageFormat::RG32_UINT => TextureFormat::Rg32Uint,
+        ReflectImageFormat::RG16_UINT => TextureFormat::Rg16Uint,
+        ReflectImageFormat::RG8_UINT => TextureFormat::Rg8Uint,
+        ReflectImageFormat::R16_UINT => TextureFormat::R16Uint,
+        ReflectImageFormat::R8_UINT => TextureFormat::R8Uint,
+        ReflectImageFormat::R64_UINT => TextureFormat::R64Uint,
+        ReflectImageFormat::R64_SINT
+        | ReflectImageFormat::Undefined
+        | ReflectImageFormat::Unknown(_) => return None,
+    };
+    Some(format)
+}
+
+fn view_dimension(
+    binding: &ReflectDescriptorBinding,
+) -> Result<wgpu::TextureViewDimension, BindGroupLayoutErrorKind> {
+    let dim = binding.image.dim;
+    let arrayed = binding.image.arrayed != 0;
+    match (dim, arrayed) {
+        (ReflectDimension::Type1d, false) => Ok(wgpu::TextureViewDimension::D1),
+        (ReflectDimension::Type2d, false) => Ok(wgpu::TextureViewDimension::D2),
+        (ReflectDimension::Type2d, true) => Ok(wgpu::TextureViewDimension::D2Array),
+        (ReflectDimension::Type3d, false) => Ok(wgpu::TextureViewDimension::D3),
+        (ReflectDimension::Cube, false) => Ok(wgpu::TextureViewDimension::Cube),
+        (ReflectDimension::Cube, true) => Ok(wgpu::TextureViewDimension::CubeArray),
+        _ => Err(BindGroupLayoutErrorKind::UnsupportedDimension { dim, arrayed }),
+    }
+}
+
+fn sample_type(binding: &ReflectDescriptorBinding) -> wgpu::TextureSampleType {
+    if binding.image.depth == 1 {
+        return wgpu::TextureSampleType::Depth;
+    }
+    let (type_flags, signedness) = match binding.type_description {
+        Some(ref type_description) => (
+            type_description.type_flags,
+            type_description.traits.numeric.scalar.signedness,
+        ),
+        None => (ReflectTypeFlags::empty(), 0),
+    };
+    if type_flags.contains(ReflectTypeFlags::INT) {
+        if signedness != 0 {
+            wgpu::TextureSampleType::Sint
+        } else {
+            wgpu::TextureSampleType::Uint
+        }
+    } else {
+        // Whether a float texture is filtered depends on the sampler it is used with, which
+        // SPIR-V doesn't tell us.
+        wgpu::TextureSampleType::Float { filterable: true }
+    }
+}
+
+fn storage_texture_access(binding: &ReflectDescriptorBinding) -> wgpu::StorageTextureAccess {
+    if !binding.access.write {
+        wgpu::StorageTextureAccess::ReadOnly
+    } else if !binding.access.read {
+        wgpu::StorageTextureAccess::WriteOnly
+    } else {
+        wgpu::StorageTextureAccess::ReadWrite
+    }
+}
+
+fn binding_type(
+    binding: &ReflectDescriptorBinding,
+) -> Result<wgpu::BindingType, BindGroupLayoutErrorKind> {
+    let binding_type = match binding.descriptor_type {
+        // Comparison samplers aren't distinguishable in SPIR-V.
+        ReflectDescriptorType::Sampler => {
+            wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering)
+        }
+        ReflectDescriptorType::SampledImage => wgpu::BindingType::Texture {
+            sample_type: sample_type(binding),
+            view_dimension: view_dimension(binding)?,
+            multisampled: binding.image.ms != 0,
+        },
+        ReflectDescriptorType::StorageImage => {
+            if binding.image.ms != 0 {
+                return Err(BindGroupLayoutErrorKind::MultisampledStorageTexture);
+            }
+            wgpu::BindingType::StorageTexture {
+                access: storage_texture_access(binding),
+                format: storage_format(binding.image.image_format).ok_or(
+                    BindGroupLayoutErrorKind::UnsupportedStorageFormat(binding.image.image_format),
+                )?,
+                view_dimension: view_dimension(binding)?,
+            }
+        }
+        ReflectDescriptorType::UniformBuffer | ReflectDescriptorType::UniformBufferDynamic => {
+            wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: binding.descriptor_type
+                    == ReflectDescriptorType::UniformBufferDynamic,
+                min_binding_size: NonZeroU64::new(u64::from(binding.block.size)),
+            }
+        }
+        // The block size of a storage buffer doesn't account for a trailing runtime array.
+        ReflectDescriptorType::StorageBuffer | ReflectDescriptorType::StorageBufferDynamic => {
+            wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage {
+                    read_only: !binding.access.write,
+                },
+                has_dynamic_offset: binding.descriptor_type
+                    == ReflectDescriptorType::StorageBufferDynamic,
+                min_binding_size: None,
+            }
+        }
+        ReflectDescriptorType::AccelerationStructureKHR => {
+            wgpu::BindingType::AccelerationStructure {
+                vertex_return: false,
+            }
+        }
+        descriptor_type => {
+            return Err(BindGroupLayoutErrorKind::UnsupportedDescriptorType(
+                descriptor_type,
+            ))
+        }
+    };
+    Ok(binding_type)
+}
+
+pub fn bind_group_layout_entry(
+    binding: &ReflectDescriptorBinding,
+    stage_flags: ReflectShaderStageFlags,
+) -> Result<wgpu::BindGroupLayoutEntry, BindGroupLayoutError> {
+    let error = |kind| BindGroupLayoutError {
+        set: binding.set,
+        binding: binding.binding,
+        name: binding.name.clone(),
+        kind,
+    };
+    let visibility = shader_stages(stage_flags)
+        .map_err(|stage| error(BindGroupLayoutErrorKind::UnsupportedStage(stage)))?;
+    let ty = binding_type(binding).map_err(error)?;
+    let count = if binding.array.dims.is_empty() {
+        None
+    } else {
+        Some(
+            NonZeroU32::new(binding.count)
+                .ok_or_else(|| error(BindGroupLayoutErrorKind::RuntimeArray))?,
+        )
+    };
+    Ok(wgpu::BindGroupLayoutEntry {
+        binding: binding.binding,
+        visibility,
+        ty,
+        count,
+    })
+}
+
+pub fn bind_group_layout_entries(
+    set: &ReflectDescriptorSet,
+    stage_flags: ReflectShaderStageFlags,
+) -> Result<Vec<wgpu::BindGroupLayoutEntry>, BindGroupLayoutError> {
+    set.bindings
+        .iter()
+        .map(|binding| bind_group_layout_entry(binding, stage_flags))
+        .collect()
+}
+
+// Uses the stages merged by `PipelineLayoutBuilder` for each binding.
+pub fn pipeline_layout_set_entries(
+    set: &PipelineLayoutSet,
+) -> Result<Vec<wgpu::BindGroupLayoutEntry>, BindGroupLayoutError> {
+    set.bindings
+        .iter()
+        .map(|binding| bind_group_layout_entry(&binding.binding, binding.stage_flags))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{descriptor_binding, numeric};
+    use crate::types::*;
+
+    fn image_binding(
+        descriptor_type: ReflectDescriptorType,
+        dim: ReflectDimension,
+        arrayed: u32,
+        image_format: ReflectImageFormat,
+        type_flags: ReflectTypeFlags,
+    ) -> ReflectDescriptorBinding {
+        ReflectDescriptorBinding {
+            count: 1,
+            image: ReflectImageTraits {
+                dim,
+                arrayed,
+                image_format,
+                ..Default::default()
+            },
+            type_description: Some(ReflectTypeDescription {
+                type_flags: type_flags | ReflectTypeFlags::EXTERNAL_IMAGE,
+                traits: ReflectTypeDescriptionTraits {
+                    numeric: numeric(32, 0, 0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ..descriptor_binding("image", 1, 3, descriptor_type)
+        }
+    }
+
+    #[test]
+    fn texture_entries() {
+        let binding = image_binding(
+            ReflectDescriptorType::SampledImage,
+            ReflectDimension::Cube,
+            1,
+            ReflectImageFormat::Undefined,
+            ReflectTypeFlags::INT,
+        );
+        let entry = bind_group_layout_entry(&binding, ReflectShaderStageFlags::FRAGMENT).unwrap();
+        assert_eq!(entry.binding, 3);
+        assert_eq!(entry.visibility, wgpu::ShaderStages::FRAGMENT);
+        assert_eq!(
+            entry.ty,
+            wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Uint,
+                view_dimension: wgpu::TextureViewDimension::CubeArray,
+                multisampled: false,
+            }
+        );
+        assert_eq!(entry.count, None);
+
+        let mut binding = image_binding(
+            ReflectDescriptorType::StorageImage,
+            ReflectDimension::Type2d,
+            0,
+            ReflectImageFormat::RGBA8,
+            ReflectTypeFlags::FLOAT,
+        );
+        binding.access = ReflectAccess {
+            read: false,
+            write: true,
+        };
+        let entry = bind_group_layout_entry(&binding, ReflectShaderStageFlags::COMPUTE).unwrap();
+        assert_eq!(
+            entry.ty,
+            wgpu::BindingType::StorageTexture {
+                access: wgpu::StorageTextureAccess::WriteOnly,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                view_dimension: wgpu::TextureViewDimension::D2,
+            }
+        );
+    }
+
+    #[test]
+    fn unsupported_bindings() {
+        let binding = image_binding(
+            ReflectDescriptorType::CombinedImageSampler,
+            ReflectDimension::Type2d,
+            0,
+            ReflectImageFormat::Undefined,
+            ReflectTypeFlags::FLOAT,
+        );
+        let err = bind_group_layout_entry(&binding, ReflectShaderStageFlags::FRAGMENT).unwrap_err();
+        assert_eq!(
+            err.kind,
+            BindGroupLayoutErrorKind::UnsupportedDescriptorType(
+                ReflectDescriptorType::CombinedImageSampler
+            )
+        );
+        assert_eq!(
+            err.to_string(),
+            "set 1 binding 3 (image): wgpu has no binding type for CombinedImageSampler"
+        );
+
+        let binding = image_binding(
+            ReflectDescriptorType::StorageImage,
+            ReflectDimension::Type2d,
+            0,
+            ReflectImageFormat::Undefined,
+            ReflectTypeFlags::FLOAT,
+        );
+        let err = bind_group_layout_entry(&binding, ReflectShaderStageFlags::COMPUTE).unwrap_err();
+        assert_eq!(
+            err.kind,
+            BindGroupLayoutErrorKind::UnsupportedStorageFormat(ReflectImageFormat::Undefined)
+        );
+
+        let binding = image_binding(
+            ReflectDescriptorType::SampledImage,
+            ReflectDimension::Type3d,
+            1,
+            ReflectImageFormat::Undefined,
+            ReflectTypeFlags::FLOAT,
+        );
+        let err = bind_group_layout_entry(&binding, ReflectShaderStageFlags::GEOMETRY).unwrap_err();
+        assert_eq!(
+            err.kind,
+            BindGroupLayoutErrorKind::UnsupportedStage(ReflectShaderStageFlags::GEOMETRY)
+        );
+        let err = bind_group_layout_entry(&binding, ReflectShaderStageFlags::VERTEX).unwrap_err();
+        assert_eq!(
+            err.kind,
+            BindGroupLayoutErrorKind::UnsupportedDimension {
+                dim: ReflectDimension::Type3d,
+                arrayed: true,
+            }
+        );
+    }
+}
@@ -0,0 +1,536 @@
+use crate::types::{ReflectBlockVariable, ReflectDecorationFlags, ReflectTypeFlags};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ScalarValue {
+    Float(f64),
+    Int(i64),
+    UInt(u64),
+    Bool(bool),
+}
+
+// Components are stored column by column; vectors have a single column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockValue {
+    pub components: Vec<ScalarValue>,
+    pub columns: u32,
+    pub rows: u32,
+}
+
+pub trait IntoBlockValue {
+    fn into_block_value(self) -> BlockValue;
+}
+
+pub trait IntoScalarValue: Copy {
+    fn into_scalar_value(self) -> ScalarValue;
+}
+
+macro_rules! impl_into_scalar_value {
+    ($variant:ident, $target:ty, $($source:ty),*) => {
+        $(
+            impl IntoScalarValue for $source {
+                fn into_scalar_value(self) -> ScalarValue {
+                    ScalarValue::$variant(self as $target)
+                }
+            }
+        )*
+    };
+}
+
+impl_into_scalar_value!(Float, f64, f32, f64);
+impl_into_scalar_value!(Int, i64, i8, i16, i32, i64);
+impl_into_scalar_value!(UInt, u64, u8, u16, u32, u64);
+
+impl IntoScalarValue for bool {
+    fn into_scalar_value(self) -> ScalarValue {
+        ScalarValue::Bool(self)
+    }
+}
+
+impl<T: IntoScalarValue> IntoBlockValue for T {
+    fn into_block_value(self) -> BlockValue {
+        BlockValue {
+            components: vec![self.into_scalar_value()],
+            columns: 1,
+            rows: 1,
+        }
+    }
+}
+
+impl<T: IntoScalarValue, const N: usize> IntoBlockValue for [T; N] {
+    fn into_block_value(self) -> BlockValue {
+        BlockValue {
+            components: self.iter().map(|value| value.into_scalar_value()).collect(),
+            columns: 1,
+            rows: N as u32,
+        }
+    }
+}
+
+// Matrices are given as an array of columns, like `Mat4::to_cols_array_2d` in most math crates.
+impl<T: IntoScalarValue, const R: usize, const C: usize> IntoBlockValue for [[T; R]; C] {
+    fn into_block_value(self) -> BlockValue {
+        BlockValue {
+            components: self
+                .iter()
+                .flat_map(|column| column.iter().map(|value| value.into_scalar_value()))
+                .collect(),
+            columns: C as u32,
+            rows: R as u32,
+        }
+    }
+}
+
+impl IntoBlockValue for BlockValue {
+    fn into_block_value(self) -> BlockValue {
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockWriteError {
+    InvalidPath(String),
+    MemberNotFound(String),
+    NotAnArray(String),
+    NotAStruct(String),
+    MissingIndex(String),
+    IndexOutOfBounds {
+        path: String,
+        index: u32,
+        len: u32,
+    },
+    TypeMismatch {
+        path: String,
+        expected: String,
+        found: String,
+    },
+    ValueOutOfRange(String),
+    UnsupportedType(String),
+    BufferTooSmall {
+        path: String,
+        end: usize,
+        len: usize,
+    },
+}
+
+impl fmt::Display for BlockWriteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BlockWriteError::InvalidPath(path) => write!(f, "invalid member path: {}", path),
+            BlockWriteError::MemberNotFound(path) => write!(f, "member not found: {}", path),
+            BlockWriteError::NotAnArray(path) => write!(f, "member is not an array: {}", path),
+            BlockWriteError::NotAStruct(path) => write!(f, "member is not a struct: {}", path),
+            BlockWriteError::MissingIndex(path) => write!(f, "missing array index: {}", path),
+            BlockWriteError::IndexOutOfBounds { path, index, len } => write!(
+                f,
+                "index {} is out of bounds for {} (length {})",
+                index, path, len
+            ),
+            BlockWriteError::TypeMismatch {
+                path,
+                expected,
+                found,
+            } => write!(
+                f,
+                "type mismatch for {}: expected {}, found {}",
+                path, expected, found
+            ),
+            BlockWriteError::ValueOutOfRange(path) => {
+                write!(f, "value does not fit the type of {}", path)
+            }
+            BlockWriteError::UnsupportedType(path) => {
+                write!(f, "member type is not supported: {}", path)
+            }
+            BlockWriteError::BufferTooSmall { path, end, len } => write!(
+                f,
+                "buffer of {} bytes is too small for {} (ends at {})",
+                len, path, end
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BlockWriteError {}
+
+// Writes values into a buffer laid out like `block`. Offsets are relative to the start of the
+// block, so push constant blocks that don't start at 0 map to the start of the buffer.
+pub struct BlockWriter<'a> {
+    block: &'a ReflectBlockVariable,
+    buffer: &'a mut [u8],
+}
+
+impl<'a> BlockWriter<'a> {
+    pub fn new(block: &'a ReflectBlockVariable, buffer: &'a mut [u8]) -> Self {
+        BlockWriter { block, buffer }
+    }
+
+    pub fn buffer(&self) -> &[u8] {
+        self.buffer
+    }
+
+    pub fn set<V: IntoBlockValue>(&mut self, path: &str, value: V) -> Result<(), BlockWriteError> {
+        let (member, offset) = self.resolve(path)?;
+        let value = value.into_block_value();
+        let type_flags = member
+            .type_description
+            .as_ref()
+            .map(|type_description| type_description.type_flags)
+            .unwrap_or_default();
+        if type_flags.contains(ReflectTypeFlags::STRUCT) {
+            return Err(BlockWriteError::TypeMismatch {
+                path: path.to_owned(),
+                expected: "struct".to_owned(),
+                found: value_shape(&value),
+            });
+        }
+
+        let numeric = &member.numeric;
+        let (columns, rows) = if type_flags.contains(ReflectTypeFlags::MATRIX) {
+            (numeric.matrix.column_count, numeric.matrix.row_count)
+        } else if type_flags.contains(ReflectTypeFlags::VECTOR) {
+            (1, numeric.vector.component_count)
+        } else {
+            (1, 1)
+        };
+        let expected = BlockValue {
+            components: Vec::new(),
+            columns,
+            rows,
+        };
+        if value.columns != columns || value.rows != rows {
+            return Err(BlockWriteError::TypeMismatch {
+                path: path.to_owned(),
+                expected: value_shape(&expected),
+                found: value_shape(&value),
+            });
+        }
+
+        let width = numeric.scalar.width / 8;
+        let row_major = member
+            .decoration_flags
+            .contains(ReflectDecorationFlags::ROW_MAJOR);
+        let mut writes = Vec::new();
+        for column in 0..columns {
+            for row in 0..rows {
+                let component_offset = if columns == 1 {
+                    row * width
+                } else if row_major {
+                    row * numeric.matrix.stride + column * width
+                } else {
+                    column * numeric.matrix.stride + row * width
+                };
+                let bytes = scalar_bytes(
+                    path,
+                    type_flags,
+                    numeric.scalar.width,
+                    numeric.scalar.signedness != 0,
+                    value.components[(column * rows + row) as usize],
+                )?;
+                writes.push((offset + component_offset as usize, bytes));
+            }
+        }
+
+        let end = writes
+            .iter()
+            .map(|(offset, bytes)| offset + bytes.len())
+            .max()
+            .unwrap_or(offset);
+        if end > self.buffer.len() {
+            return Err(BlockWriteError::BufferTooSmall {
+                path: path.to_owned(),
+                end,
+                len: self.buffer.len(),
+            });
+        }
+        for (offset, bytes) in writes {
+            self.buffer[offset..offset + bytes.len()].copy_from_slice(&bytes);
+        }
+        Ok(())
+    }
+
+    fn resolve(&self, path: &str) -> Result<(&'a ReflectBlockVariable, usize), BlockWriteError> {
+        let mut members = &self.block.members;
+        let mut offset = 0usize;
+        let mut current: Option<&'a ReflectBlockVariable> = None;
+        for component in path.split('.') {
+            let (name, indices) = parse_component(path, component)?;
+            if let Some(parent) = current {
+                let is_struct = parent
+                    .type_description
+                    .as_ref()
+                    .is_some_and(|td| td.type_flags.contains(ReflectTypeFlags::STRUCT));
+                if !is_struct {
+                    return Err(BlockWriteError::NotAStruct(path.to_owned()));
+                }
+            }
+            let member = members
+                .iter()
+                .find(|member| member.name == name)
+                .ok_or_else(|| BlockWriteError::MemberNotFound(path.to_owned()))?;
+            offset += match current {
+                Some(_) => member.offset as usize,
+                None => member.offset.saturating_sub(self.block.offset) as usize,
+            };
+
+            let dims = &member.array.dims;
+            if indices.is_empty() && !dims.is_empty() {
+                return Err(BlockWriteError::MissingIndex(path.to_owned()));
+            }
+            if !indices.is_empty() && indices.len() != dims.len() {
+                return Err(match dims.len() {
+                    0 => BlockWriteError::NotAnArray(path.to_owned()),
+                    _ => BlockWriteError::MissingIndex(path.to_owned()),
+                });
+            }
+            let mut stride = member.array.stride as usize;
+            for (&index, &len) in indices.iter().zip(dims).rev() {
+                if index >= len {
+                    return Err(BlockWriteError::IndexOutOfBounds {
+                        path: path.to_owned(),
+                        index,
+                        len,
+                    });
+                }
+                offset += index as usize * stride;
+                stride *= len as usize;
+            }
+
+            members = &member.members;
+            current = Some(member);
+        }
+        current
+            .map(|member| (member, offset))
+            .ok_or_else(|| BlockWriteError::InvalidPath(path.to_owned()))
+    }
+}
+
+fn parse_component<'p>(
+    path: &str,
+    component: &'p str,
+) -> Result<(&'p str, Vec<u32>), BlockWriteError> {
+    let invalid = || BlockWriteError::InvalidPath(path.to_owned());
+    let (name, mut rest) = match component.find('[') {
+        Some(bracket) => component.split_at(bracket),
+        None => (component, ""),
+    };
+    if name.is_empty() {
+        return Err(invalid());
+    }
+    let mut indices = Vec::new();
+    while !rest.is_empty() {
+        let close = rest.find(']').ok_or_else(invalid)?;
+        if !rest.starts_with('[') {
+            return Err(invalid());
+        }
+        indices.push(rest[1..close].trim().parse().map_err(|_| invalid())?);
+        rest = &rest[close + 1..];
+    }
+    Ok((name, indices))
+}
+
+fn value_shape(value: &BlockValue) -> String {
+    match (value.columns, value.rows) {
+        (1, 1) => "scalar".to_owned(),
+        (1, rows) => format!("vector of {}", rows),
+        (columns, rows) => format!("{}x{} matrix", columns, rows),
+    }
+}
+
+fn scalar_bytes(
+    path: &str,
+    type_flags: ReflectTypeFlags,
+    width: u32,
+    signed: bool,
+    value: ScalarValue,
+) -> Result<Vec<u8>, BlockWriteError> {
+    let out_of_range = || BlockWriteError::ValueOutOfRange(path.to_owned());
+    let mismatch = |expected: &str| BlockWriteError::TypeMismatch {
+        path: path.to_owned(),
+        expected: expected.to_owned(),
+        found: format!("{:?}", value),
+    };
+
+    if type_flags.contains(ReflectTypeFlags::FLOAT) {
+        let value = match value {
+            ScalarValue::Float(value) => value,
+            _ => return Err(mismatch("float")),
+        };
+        return match width {
+            32 => Ok((value as f32).to_le_bytes().to_vec()),
+            64 => Ok(value.to_le_bytes().to_vec()),
+            _ => Err(BlockWriteError::UnsupportedType(path.to_owned())),
+        };
+    }
+
+    if type_flags.contains(ReflectTypeFlags::BOOL) {
+        return match value {
+            ScalarValue::Bool(value) => Ok((value as u32).to_le_bytes().to_vec()),
+            _ => Err(mismatch("bool")),
+        };
+    }
+
+    if type_flags.contains(ReflectTypeFlags::INT) {
+        let value = match value {
+            ScalarValue::Int(value) => value as i128,
+            ScalarValue::UInt(value) => value as i128,
+            _ => return Err(mismatch("integer")),
+        };
+        let (min, max) = match (width, signed) {
+            (8 | 16 | 32 | 64, true) => (-(1i128 << (width - 1)), (1i128 << (width - 1)) - 1),
+            (8 | 16 | 32 | 64, false) => (0, (1i128 << width) - 1),
+            _ => return Err(BlockWriteError::UnsupportedType(path.to_owned())),
+        };
+        if value < min || value > max {
+            return Err(out_of_range());
+        }
+        return Ok(value.to_le_bytes()[..(width / 8) as usize].to_vec());
+    }
+
+    Err(BlockWriteError::UnsupportedType(path.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{block_member, numeric};
+    use crate::types::*;
+
+    fn globals() -> ReflectBlockVariable {
+        let float = ReflectTypeFlags::FLOAT;
+        let mut lights = block_member(
+            "lights",
+            0,
+            ReflectTypeFlags::STRUCT | ReflectTypeFlags::ARRAY,
+            ReflectNumericTraits::default(),
+        );
+        lights.array = ReflectArrayTraits {
+            dims: vec![4],
+            stride: 32,
+        };
+        lights.members = vec![
+            block_member(
+                "color",
+                0,
+                float | ReflectTypeFlags::VECTOR,
+                numeric(32, 0, 3),
+            ),
+            block_member("intensity", 12, float, numeric(32, 0, 0)),
+        ];
+        let mut transform = block_member(
+            "transform",
+            128,
+            float | ReflectTypeFlags::VECTOR | ReflectTypeFlags::MATRIX,
+            ReflectNumericTraits {
+                matrix: ReflectNumericTraitsMatrix {
+                    column_count: 2,
+                    row_count: 2,
+                    stride: 16,
+                },
+                ..numeric(32, 0, 2)
+            },
+        );
+        transform.decoration_flags = ReflectDecorationFlags::COLUMN_MAJOR;
+        ReflectBlockVariable {
+            name: "Globals".to_owned(),
+            size: 176,
+            members: vec![
+                lights,
+                transform,
+                block_member("count", 160, ReflectTypeFlags::INT, numeric(32, 1, 0)),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn read_f32(buffer: &[u8], offset: usize) -> f32 {
+        f32::from_le_bytes([
+            buffer[offset],
+            buffer[offset + 1],
+            buffer[offset + 2],
+            buffer[offset + 3],
+        ])
+    }
+
+    #[test]
+    fn write_members() {
+        let block = globals();
+        let mut buffer = vec![0u8; 176];
+        let mut writer = BlockWriter::new(&block, &mut buffer);
+        writer.set("lights[3].color", [1.0, 0.5, 0.0]).unwrap();
+        writer.set("lights[3].intensity", 2.0f32).unwrap();
+        writer
+            .set("transform", [[1.0f32, 2.0], [3.0, 4.0]])
+            .unwrap();
+        writer.set("count", -3).unwrap();
+
+        assert_eq!(read_f32(&buffer, 96), 1.0);
+        assert_eq!(read_f32(&buffer, 100), 0.5);
+        assert_eq!(read_f32(&buffer, 108), 2.0);
+        assert_eq!(read_f32(&buffer, 128), 1.0);
+        assert_eq!(read_f32(&buffer, 132), 2.0);
+        assert_eq!(read_f32(&buffer, 144), 3.0);
+        assert_eq!(read_f32(&buffer, 148), 4.0);
+        assert_eq!(buffer[160..164], (-3i32).to_le_bytes());
+    }
+
+    #[test]
+    fn write_errors() {
+        let block = globals();
+        let mut buffer = vec![0u8; 176];
+        let mut writer = BlockWriter::new(&block, &mut buffer);
+        assert_eq!(
+            writer.set("lights[4].intensity", 1.0),
+            Err(BlockWriteError::IndexOutOfBounds {
+                path: "lights[4].intensity".to_owned(),
+                index: 4,
+                len: 4
+            })
+        );
+        assert_eq!(
+            writer.set("lights.intensity", 1.0),
+            Err(BlockWriteError::MissingIndex("lights.intensity".to_owned()))
+        );
+        assert_eq!(
+            writer.set("count[0]", 1),
+            Err(BlockWriteError::NotAnArray("count[0]".to_owned()))
+        );
+        assert_eq!(
+            writer.set("count.x", 1),
+            Err(BlockWriteError::NotAStruct("count.x".to_owned()))
+        );
+        assert_eq!(
+            writer.set("lights[0].radius", 1.0),
+            Err(BlockWriteError::MemberNotFound(
+                "lights[0].radius".to_owned()
+            ))
+        );
+        assert_eq!(
+            writer.set("lights[0].color", [1.0, 0.5]),
+            Err(BlockWriteError::TypeMismatch {
+                path: "lights[0].color".to_owned(),
+                expected: "vector of 3".to_owned(),
+                found: "vector of 2".to_owned()
+            })
+        );
+        assert!(matches!(
+            writer.set("count", 1.0),
+            Err(BlockWriteError::TypeMismatch { .. })
+        ));
+        assert_eq!(
+            writer.set("count", u64::MAX),
+            Err(BlockWriteError::ValueOutOfRange("count".to_owned()))
+        );
+        assert!(writer.buffer().iter().all(|&byte| byte == 0));
+
+        let mut short = vec![0u8; 100];
+        let mut writer = BlockWriter::new(&block, &mut short);
+        assert_eq!(
+            writer.set("count", 1),
+            Err(BlockWriteError::BufferTooSmall {
+                path: "count".to_owned(),
+                end: 164,
+                len: 100
+            })
+        );
+    }
+}
@@ -0,0 +1,28 @@
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct RGB {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+unsafe impl bytemuck::Zeroable for RGB {}
+unsafe impl bytemuck::Pod for RGB {}
+const _: () = assert!(std::mem::size_of::<RGB>() == 12);
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct MyConstants {
+    pub xform_matrix: [[f32; 4]; 4],
+    pub scale: [f32; 3],
+    pub _pad0: [u8; 4],
+    pub rgb: RGB,
+    pub _pad1: [u8; 4],
+    pub t: f32,
+    pub uv: [f32; 2],
+    pub _pad2: [u8; 4],
+}
+
+unsafe impl bytemuck::Zeroable for MyConstants {}
+unsafe impl bytemuck::Pod for MyConstants {}
+const _: () = assert!(std::mem::size_of::<MyConstants>() == 112);
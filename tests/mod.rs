@@ -4,6 +4,184 @@ extern crate spirv_reflect;
 mod tests {
     use spirv_reflect::*;
 
+    fn spirv_string(value: &str) -> Vec<u32> {
+        let mut bytes = value.as_bytes().to_vec();
+        bytes.resize((bytes.len() / 4 + 1) * 4, 0);
+        bytes
+            .chunks(4)
+            .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect()
+    }
+
+    fn spirv_instruction(opcode: u32, operands: &[u32]) -> Vec<u32> {
+        let mut words = vec![((operands.len() as u32 + 1) << 16) | opcode];
+        words.extend_from_slice(operands);
+        words
+    }
+
+    // Minimal compute shader declaring three specialization constants:
+    //   layout(constant_id = 0) const bool enable = true;
+    //   layout(constant_id = 1) const int offset = -7;
+    //   layout(constant_id = 2) const float scale = 1.5;
+    fn spec_constant_module() -> Vec<u32> {
+        let mut code = vec![0x0723_0203, 0x0001_0000, 0, 11, 0];
+        code.extend(spirv_instruction(17, &[1])); // OpCapability Shader
+        code.extend(spirv_instruction(14, &[0, 1])); // OpMemoryModel Logical GLSL450
+        let mut entry_point = vec![5, 9];
+        entry_point.extend(spirv_string("main"));
+        code.extend(spirv_instruction(15, &entry_point)); // OpEntryPoint GLCompute %9 "main"
+        code.extend(spirv_instruction(16, &[9, 17, 1, 1, 1])); // OpExecutionMode %9 LocalSize 1 1 1
+        for (id, name) in &[(6, "enable"), (7, "offset"), (8, "scale")] {
+            let mut operands = vec![*id];
+            operands.extend(spirv_string(name));
+            code.extend(spirv_instruction(5, &operands)); // OpName
+        }
+        code.extend(spirv_instruction(71, &[6, 1, 0])); // OpDecorate %6 SpecId 0
+        code.extend(spirv_instruction(71, &[7, 1, 1])); // OpDecorate %7 SpecId 1
+        code.extend(spirv_instruction(71, &[8, 1, 2])); // OpDecorate %8 SpecId 2
+        code.extend(spirv_instruction(19, &[1])); // %1 = OpTypeVoid
+        code.extend(spirv_instruction(33, &[2, 1])); // %2 = OpTypeFunction %1
+        code.extend(spirv_instruction(20, &[3])); // %3 = OpTypeBool
+        code.extend(spirv_instruction(21, &[4, 32, 1])); // %4 = OpTypeInt 32 1
+        code.extend(spirv_instruction(22, &[5, 32])); // %5 = OpTypeFloat 32
+        code.extend(spirv_instruction(48, &[3, 6])); // %6 = OpSpecConstantTrue %3
+        code.extend(spirv_instruction(50, &[4, 7, (-7i32) as u32])); // %7 = OpSpecConstant %4 -7
+        code.extend(spirv_instruction(50, &[5, 8, 1.5f32.to_bits()])); // %8 = OpSpecConstant %5 1.5
+        code.extend(spirv_instruction(54, &[1, 9, 0, 2])); // %9 = OpFunction %1 None %2
+        code.extend(spirv_instruction(248, &[10])); // %10 = OpLabel
+        code.extend(spirv_instruction(253, &[])); // OpReturn
+        code.extend(spirv_instruction(56, &[])); // OpFunctionEnd
+        code
+    }
+
+    // Minimal shader declaring one float vector per `(name, location, component count, flat)`
+    // in `storage_class` (1 = Input, 3 = Output).
+    fn interface_module(
+        execution_model: u32,
+        storage_class: u32,
+        variables: &[(&str, u32, u32, bool)],
+    ) -> Vec<u32> {
+        let mut code = vec![0x0723_0203, 0x0001_0000, 0, 102, 0];
+        code.extend(spirv_instruction(17, &[1])); // OpCapability Shader
+        code.extend(spirv_instruction(14, &[0, 1])); // OpMemoryModel Logical GLSL450
+        let mut entry_point = vec![execution_model, 100];
+        entry_point.extend(spirv_string("main"));
+        entry_point.extend((0..variables.len() as u32).map(|index| 11 + 2 * index));
+        code.extend(spirv_instruction(15, &entry_point)); // OpEntryPoint
+        if execution_model == 4 {
+            code.extend(spirv_instruction(16, &[100, 7])); // OpExecutionMode %100 OriginUpperLeft
+        }
+        for (index, &(name, location, _, flat)) in variables.iter().enumerate() {
+            let id = 11 + 2 * index as u32;
+            let mut operands = vec![id];
+            operands.extend(spirv_string(name));
+            code.extend(spirv_instruction(5, &operands)); // OpName
+            code.extend(spirv_instruction(71, &[id, 30, location])); // OpDecorate Location
+            if flat {
+                code.extend(spirv_instruction(71, &[id, 14])); // OpDecorate Flat
+            }
+        }
+        code.extend(spirv_instruction(19, &[1])); // %1 = OpTypeVoid
+        code.extend(spirv_instruction(33, &[2, 1])); // %2 = OpTypeFunction %1
+        code.extend(spirv_instruction(22, &[3, 32])); // %3 = OpTypeFloat 32
+        for count in 2..5 {
+            code.extend(spirv_instruction(23, &[2 + count, 3, count])); // OpTypeVector %3 count
+        }
+        for (index, &(_, _, component_count, _)) in variables.iter().enumerate() {
+            let id = 10 + 2 * index as u32;
+            let type_id = if component_count == 1 {
+                3
+            } else {
+                2 + component_count
+            };
+            code.extend(spirv_instruction(32, &[id, storage_class, type_id])); // OpTypePointer
+            code.extend(spirv_instruction(59, &[id, id + 1, storage_class])); // OpVariable
+        }
+        code.extend(spirv_instruction(54, &[1, 100, 0, 2])); // %100 = OpFunction %1 None %2
+        code.extend(spirv_instruction(248, &[101])); // %101 = OpLabel
+        code.extend(spirv_instruction(253, &[])); // OpReturn
+        code.extend(spirv_instruction(56, &[])); // OpFunctionEnd
+        code
+    }
+
+    // Minimal fragment shader declaring two samplers, of which only `used` is loaded:
+    //   layout(set = 0, binding = 0) uniform sampler used;
+    //   layout(set = 0, binding = 1) uniform sampler unused;
+    fn sampler_module() -> Vec<u32> {
+        let mut code = vec![0x0723_0203, 0x0001_0000, 0, 103, 0];
+        code.extend(spirv_instruction(17, &[1])); // OpCapability Shader
+        code.extend(spirv_instruction(14, &[0, 1])); // OpMemoryModel Logical GLSL450
+        let mut entry_point = vec![4, 100];
+        entry_point.extend(spirv_string("main"));
+        code.extend(spirv_instruction(15, &entry_point)); // OpEntryPoint Fragment %100 "main"
+        code.extend(spirv_instruction(16, &[100, 7])); // OpExecutionMode %100 OriginUpperLeft
+        for (id, binding, name) in &[(5, 0, "used"), (6, 1, "unused")] {
+            let mut operands = vec![*id];
+            operands.extend(spirv_string(name));
+            code.extend(spirv_instruction(5, &operands)); // OpName
+            code.extend(spirv_instruction(71, &[*id, 34, 0])); // OpDecorate DescriptorSet 0
+            code.extend(spirv_instruction(71, &[*id, 33, *binding])); // OpDecorate Binding
+        }
+        code.extend(spirv_instruction(19, &[1])); // %1 = OpTypeVoid
+        code.extend(spirv_instruction(33, &[2, 1])); // %2 = OpTypeFunction %1
+        code.extend(spirv_instruction(26, &[3])); // %3 = OpTypeSampler
+        code.extend(spirv_instruction(32, &[4, 0, 3])); // %4 = OpTypePointer UniformConstant %3
+        code.extend(spirv_instruction(59, &[4, 5, 0])); // %5 = OpVariable %4 UniformConstant
+        code.extend(spirv_instruction(59, &[4, 6, 0])); // %6 = OpVariable %4 UniformConstant
+        code.extend(spirv_instruction(54, &[1, 100, 0, 2])); // %100 = OpFunction %1 None %2
+        code.extend(spirv_instruction(248, &[101])); // %101 = OpLabel
+        code.extend(spirv_instruction(61, &[3, 102, 5])); // %102 = OpLoad %3 %5
+        code.extend(spirv_instruction(253, &[])); // OpReturn
+        code.extend(spirv_instruction(56, &[])); // OpFunctionEnd
+        code
+    }
+
+    // Compute shader reading a DXC-style RWByteAddressBuffer at a folded and an unfolded
+    // constant address:
+    //   RWByteAddressBuffer data : register(u0);
+    //   data.Load(16); data.Load(20);
+    fn byte_address_buffer_module() -> Vec<u32> {
+        let mut code = vec![0x0723_0203, 0x0001_0000, 0, 107, 0];
+        code.extend(spirv_instruction(17, &[1])); // OpCapability Shader
+        code.extend(spirv_instruction(14, &[0, 1])); // OpMemoryModel Logical GLSL450
+        let mut entry_point = vec![5, 100];
+        entry_point.extend(spirv_string("main"));
+        code.extend(spirv_instruction(15, &entry_point)); // OpEntryPoint GLCompute %100 "main"
+        code.extend(spirv_instruction(16, &[100, 17, 1, 1, 1])); // OpExecutionMode LocalSize 1 1 1
+        let mut name = vec![7];
+        name.extend(spirv_string("data"));
+        code.extend(spirv_instruction(5, &name)); // OpName %7 "data"
+        code.extend(spirv_instruction(71, &[4, 6, 4])); // OpDecorate %4 ArrayStride 4
+        code.extend(spirv_instruction(71, &[5, 3])); // OpDecorate %5 BufferBlock
+        code.extend(spirv_instruction(72, &[5, 0, 35, 0])); // OpMemberDecorate %5 0 Offset 0
+        code.extend(spirv_instruction(71, &[7, 34, 0])); // OpDecorate %7 DescriptorSet 0
+        code.extend(spirv_instruction(71, &[7, 33, 0])); // OpDecorate %7 Binding 0
+        let mut user_type = vec![7, 5636];
+        user_type.extend(spirv_string("rwbyteaddressbuffer"));
+        code.extend(spirv_instruction(5632, &user_type)); // OpDecorateString UserTypeGOOGLE
+        code.extend(spirv_instruction(19, &[1])); // %1 = OpTypeVoid
+        code.extend(spirv_instruction(33, &[2, 1])); // %2 = OpTypeFunction %1
+        code.extend(spirv_instruction(21, &[3, 32, 0])); // %3 = OpTypeInt 32 0
+        code.extend(spirv_instruction(29, &[4, 3])); // %4 = OpTypeRuntimeArray %3
+        code.extend(spirv_instruction(30, &[5, 4])); // %5 = OpTypeStruct %4
+        code.extend(spirv_instruction(32, &[6, 2, 5])); // %6 = OpTypePointer Uniform %5
+        code.extend(spirv_instruction(59, &[6, 7, 2])); // %7 = OpVariable %6 Uniform
+        for (id, value) in &[(8, 0), (9, 2), (10, 4), (11, 20)] {
+            code.extend(spirv_instruction(43, &[3, *id, *value])); // OpConstant %3
+        }
+        code.extend(spirv_instruction(32, &[12, 2, 3])); // %12 = OpTypePointer Uniform %3
+        code.extend(spirv_instruction(54, &[1, 100, 0, 2])); // %100 = OpFunction %1 None %2
+        code.extend(spirv_instruction(248, &[101])); // %101 = OpLabel
+        code.extend(spirv_instruction(65, &[12, 102, 7, 8, 10])); // %102 = OpAccessChain %7 0 4
+        code.extend(spirv_instruction(61, &[3, 103, 102])); // %103 = OpLoad %3 %102
+        code.extend(spirv_instruction(194, &[3, 104, 11, 9])); // %104 = OpShiftRightLogical 20 2
+        code.extend(spirv_instruction(65, &[12, 105, 7, 8, 104])); // %105 = OpAccessChain %7 0 %104
+        code.extend(spirv_instruction(61, &[3, 106, 105])); // %106 = OpLoad %3 %105
+        code.extend(spirv_instruction(253, &[])); // OpReturn
+        code.extend(spirv_instruction(56, &[])); // OpFunctionEnd
+        code
+    }
+
     #[test]
     fn load_module() {
         let ps_data = include_bytes!("./ImGuiPs.spirv");
@@ -59,12 +237,1252 @@ mod tests {
 
         let tex_descriptor = &descriptor_set.bindings[0];
         module
-            .change_descriptor_binding_numbers(&tex_descriptor, 30, Some(1))
+            .change_descriptor_binding_numbers(tex_descriptor, 30, Some(1))
             .unwrap();
 
         let smp_descriptor = &descriptor_set.bindings[1];
         module
-            .change_descriptor_binding_numbers(&smp_descriptor, 4, Some(2))
+            .change_descriptor_binding_numbers(smp_descriptor, 4, Some(2))
+            .unwrap();
+    }
+
+    #[test]
+    fn enumerate_capabilities() {
+        let ps_data = include_bytes!("./ImGuiPs.spirv");
+        let module = ShaderModule::load_u8_data(ps_data).unwrap();
+        let capabilities = module.enumerate_capabilities().unwrap();
+        assert!(capabilities
+            .iter()
+            .any(|capability| capability.value == Some(spirv::Capability::Shader)));
+
+        let module = ShaderModule::load_u32_data(&spec_constant_module()).unwrap();
+        let capabilities = module.enumerate_capabilities().unwrap();
+        assert_eq!(capabilities.len(), 1);
+        assert_eq!(capabilities[0].value, Some(spirv::Capability::Shader));
+        assert_eq!(capabilities[0].word_offset, 5);
+    }
+
+    #[test]
+    fn enumerate_execution_modes() {
+        let ps_data = include_bytes!("./ImGuiPs.spirv");
+        let module = ShaderModule::load_u8_data(ps_data).unwrap();
+        let entry_points = module.enumerate_entry_points().unwrap();
+        assert_eq!(entry_points.len(), 1);
+        assert_eq!(
+            entry_points[0].execution_modes,
+            vec![types::ReflectExecutionMode::OriginUpperLeft]
+        );
+
+        let module = ShaderModule::load_u32_data(&spec_constant_module()).unwrap();
+        let entry_points = module.enumerate_entry_points().unwrap();
+        assert_eq!(entry_points.len(), 1);
+        assert_eq!(
+            entry_points[0].execution_modes,
+            vec![types::ReflectExecutionMode::LocalSize(
+                types::ReflectEntryPointLocalSize { x: 1, y: 1, z: 1 }
+            )]
+        );
+
+        // Splice in LocalSizeId and a DepthReplacing with an operand it doesn't take after the
+        // LocalSize execution mode.
+        let mut code = spec_constant_module();
+        let mut modes = spirv_instruction(331, &[9, 38, 7, 7, 7]); // OpExecutionModeId LocalSizeId
+        modes.extend(spirv_instruction(16, &[9, 12, 5])); // OpExecutionMode DepthReplacing 5
+        code.splice(21..21, modes);
+        let module = ShaderModule::load_u32_data(&code).unwrap();
+        let entry_points = module.enumerate_entry_points().unwrap();
+        assert_eq!(
+            entry_points[0].execution_modes[1..],
+            [
+                types::ReflectExecutionMode::LocalSizeId(types::ReflectEntryPointLocalSizeId {
+                    x_id: 7,
+                    y_id: 7,
+                    z_id: 7
+                }),
+                types::ReflectExecutionMode::Unknown {
+                    mode: 12,
+                    operands: vec![5]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn enumerate_specialization_constants() {
+        let ps_data = include_bytes!("./ImGuiPs.spirv");
+        let module = ShaderModule::load_u8_data(ps_data).unwrap();
+        assert!(module
+            .enumerate_specialization_constants()
+            .unwrap()
+            .is_empty());
+
+        let module = ShaderModule::load_u32_data(&spec_constant_module()).unwrap();
+        let constants = module.enumerate_specialization_constants().unwrap();
+        assert_eq!(constants.len(), 3);
+
+        let enable = &constants[0];
+        assert_eq!(enable.name, "enable");
+        assert_eq!(enable.constant_id, 0);
+        assert_eq!(enable.type_flags, types::ReflectTypeFlags::BOOL);
+        assert_eq!(
+            enable.default_value,
+            types::ReflectSpecializationConstantValue::Bool(true)
+        );
+
+        let offset = &constants[1];
+        assert_eq!(offset.name, "offset");
+        assert_eq!(offset.constant_id, 1);
+        assert_eq!(offset.type_flags, types::ReflectTypeFlags::INT);
+        assert_eq!(offset.scalar.width, 32);
+        assert_eq!(offset.scalar.signedness, 1);
+        assert_eq!(
+            offset.default_value,
+            types::ReflectSpecializationConstantValue::Int(-7)
+        );
+
+        let scale = &constants[2];
+        assert_eq!(scale.name, "scale");
+        assert_eq!(scale.constant_id, 2);
+        assert_eq!(scale.type_flags, types::ReflectTypeFlags::FLOAT);
+        assert_eq!(scale.scalar.width, 32);
+        assert_eq!(
+            scale.default_value,
+            types::ReflectSpecializationConstantValue::Float(1.5)
+        );
+    }
+
+    #[test]
+    fn clone_drop_original_first() {
+        let ps_data = include_bytes!("./ImGuiPs.spirv");
+        let module = ShaderModule::load_u8_data(ps_data).unwrap();
+        let cloned = module.try_clone().unwrap();
+        drop(module);
+        assert_eq!(cloned.enumerate_descriptor_bindings(None).unwrap().len(), 2);
+        drop(cloned);
+    }
+
+    #[test]
+    fn clone_drop_clone_first() {
+        let ps_data = include_bytes!("./ImGuiPs.spirv");
+        let module = ShaderModule::load_u8_data(ps_data).unwrap();
+        let cloned = module.try_clone().unwrap();
+        drop(cloned);
+        assert_eq!(module.enumerate_descriptor_bindings(None).unwrap().len(), 2);
+        drop(module);
+    }
+
+    #[test]
+    fn clone_default_module() {
+        let module = ShaderModule::default();
+        let cloned = module.try_clone().unwrap();
+        assert!(cloned.get_code().is_empty());
+    }
+
+    #[test]
+    fn clone_then_mutate_clone() {
+        let ps_data = include_bytes!("./ImGuiPs.spirv");
+        let module = ShaderModule::load_u8_data(ps_data).unwrap();
+        let mut cloned = module.try_clone().unwrap();
+
+        let bindings = cloned.enumerate_descriptor_bindings(None).unwrap();
+        cloned
+            .change_descriptor_binding_numbers(&bindings[0], 30, Some(1))
+            .unwrap();
+        drop(bindings);
+
+        let cloned_bindings = cloned.enumerate_descriptor_bindings(None).unwrap();
+        assert!(cloned_bindings
+            .iter()
+            .any(|binding| binding.binding == 30 && binding.set == 1));
+        let bindings = module.enumerate_descriptor_bindings(None).unwrap();
+        assert!(bindings.iter().all(|binding| binding.binding != 30));
+        assert_ne!(module.get_code(), cloned.get_code());
+
+        drop(cloned);
+        assert_eq!(module.enumerate_descriptor_bindings(None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn mutate_then_clone() {
+        let ps_data = include_bytes!("./ImGuiPs.spirv");
+        let mut module = ShaderModule::load_u8_data(ps_data).unwrap();
+
+        let bindings = module.enumerate_descriptor_bindings(None).unwrap();
+        module
+            .change_descriptor_binding_numbers(&bindings[1], 4, Some(2))
+            .unwrap();
+        drop(bindings);
+
+        let cloned = module.try_clone().unwrap();
+        assert_eq!(module.get_code(), cloned.get_code());
+        drop(module);
+
+        let cloned_bindings = cloned.enumerate_descriptor_bindings(None).unwrap();
+        assert!(cloned_bindings
+            .iter()
+            .any(|binding| binding.binding == 4 && binding.set == 2));
+
+        let mut recloned = cloned.try_clone().unwrap();
+        drop(cloned);
+        let bindings = recloned.enumerate_descriptor_bindings(None).unwrap();
+        recloned
+            .change_descriptor_binding_numbers(&bindings[0], 7, None)
+            .unwrap();
+    }
+
+    #[test]
+    fn change_binding_numbers_with_stale_binding() {
+        let ps_data = include_bytes!("./ImGuiPs.spirv");
+        let mut module = ShaderModule::load_u8_data(ps_data).unwrap();
+
+        let bindings = module.enumerate_descriptor_bindings(None).unwrap();
+        let tex_descriptor = &bindings[0];
+        module
+            .change_descriptor_binding_numbers(tex_descriptor, 30, Some(1))
+            .unwrap();
+
+        // The reflected binding still describes set 0, so it no longer matches the module.
+        assert!(module
+            .change_descriptor_binding_numbers(tex_descriptor, 31, None)
+            .is_err());
+
+        let bindings = module.enumerate_descriptor_bindings(None).unwrap();
+        let tex_descriptor = bindings
+            .iter()
+            .find(|binding| binding.name == "tex")
+            .unwrap();
+        module
+            .change_descriptor_binding_numbers(tex_descriptor, 31, None)
+            .unwrap();
+    }
+
+    #[test]
+    fn change_numbers_with_foreign_values() {
+        let ps_data = include_bytes!("./ImGuiPs.spirv");
+        let mut module = ShaderModule::load_u8_data(ps_data).unwrap();
+
+        let sample_data = include_bytes!("../examples/sample.spv");
+        let sample = ShaderModule::load_u8_data(sample_data).unwrap();
+        let sample_bindings = sample.enumerate_descriptor_bindings(None).unwrap();
+        let sample_sets = sample.enumerate_descriptor_sets(None).unwrap();
+        let sample_inputs = sample.enumerate_input_variables(None).unwrap();
+        drop(sample);
+
+        for binding in &sample_bindings {
+            assert!(module
+                .change_descriptor_binding_numbers(binding, 5, None)
+                .is_err());
+        }
+        for set in &sample_sets {
+            assert!(module.change_descriptor_set_number(set, 5).is_err());
+        }
+        for variable in &sample_inputs {
+            assert!(module.change_input_variable_location(variable, 5).is_err());
+        }
+    }
+
+    #[test]
+    fn change_set_number() {
+        let ps_data = include_bytes!("./ImGuiPs.spirv");
+        let mut module = ShaderModule::load_u8_data(ps_data).unwrap();
+
+        let descriptor_sets = module.enumerate_descriptor_sets(None).unwrap();
+        module
+            .change_descriptor_set_number(&descriptor_sets[0], 3)
+            .unwrap();
+        assert!(module
+            .change_descriptor_set_number(&descriptor_sets[0], 4)
+            .is_err());
+
+        let descriptor_sets = module.enumerate_descriptor_sets(None).unwrap();
+        assert_eq!(descriptor_sets[0].set, 3);
+    }
+
+    #[test]
+    fn load_invalid_magic_number() {
+        let mut code = spec_constant_module();
+        code[0] = 0xdead_beef;
+        match ShaderModule::load_u32_data(&code) {
+            Err(ref err) if err.kind() == error::ReflectErrorKind::SpirvInvalidMagicNumber => {}
+            Err(err) => panic!("unexpected error: {}", err),
+            Ok(_) => panic!("module with an invalid magic number was accepted"),
+        }
+    }
+
+    #[test]
+    fn stale_binding_error_context() {
+        let ps_data = include_bytes!("./ImGuiPs.spirv");
+        let mut module = ShaderModule::load_u8_data(ps_data).unwrap();
+
+        let bindings = module.enumerate_descriptor_bindings(None).unwrap();
+        let tex_descriptor = &bindings[0];
+        module
+            .change_descriptor_binding_numbers(tex_descriptor, 30, Some(1))
+            .unwrap();
+
+        let err = module
+            .change_descriptor_binding_numbers(tex_descriptor, 31, None)
+            .unwrap_err();
+        assert_eq!(err.kind(), error::ReflectErrorKind::ElementNotFound);
+        let context = err.context();
+        assert_eq!(context.set, Some(tex_descriptor.set));
+        assert_eq!(context.binding, Some(tex_descriptor.binding));
+        assert_eq!(context.word_offset, Some(tex_descriptor.word_offset.0));
+    }
+
+    #[test]
+    fn pipeline_layout_conflicting_descriptor_types() {
+        let ps_data = include_bytes!("./ImGuiPs.spirv");
+        let module = ShaderModule::load_u8_data(ps_data).unwrap();
+
+        // The texture and sampler share set 0, binding 0.
+        let mut builder = pipeline::PipelineLayoutBuilder::new();
+        builder.add_module(&module).unwrap();
+        let conflicts = builder.build().unwrap_err();
+        assert_eq!(conflicts.len(), 1);
+        match conflicts[0] {
+            pipeline::PipelineLayoutConflict::DescriptorType {
+                set: 0, binding: 0, ..
+            } => {}
+            ref conflict => panic!("unexpected conflict: {:?}", conflict),
+        }
+    }
+
+    #[test]
+    fn pipeline_layout_push_constant_overflow() {
+        let block = types::ReflectBlockVariable {
+            offset: u32::MAX - 3,
+            size: 16,
+            ..Default::default()
+        };
+        let mut builder = pipeline::PipelineLayoutBuilder::new();
+        builder.add_push_constant_block(&block, types::ReflectShaderStageFlags::VERTEX);
+        assert_eq!(
+            builder.build().unwrap_err(),
+            vec![
+                pipeline::PipelineLayoutConflict::PushConstantRangeOverflow {
+                    stage_flags: types::ReflectShaderStageFlags::VERTEX,
+                    offset: u32::MAX - 3,
+                    size: 16,
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn pipeline_layout_merge_modules() {
+        let ps_data = include_bytes!("./ImGuiPs.spirv");
+        let mut module = ShaderModule::load_u8_data(ps_data).unwrap();
+        let bindings = module.enumerate_descriptor_bindings(None).unwrap();
+        let smp_descriptor = bindings
+            .iter()
+            .find(|binding| binding.name == "smp")
+            .unwrap();
+        module
+            .change_descriptor_binding_numbers(smp_descriptor, 1, None)
+            .unwrap();
+
+        let sample_data = include_bytes!("../examples/sample.spv");
+        let sample = ShaderModule::load_u8_data(sample_data).unwrap();
+
+        let layout = pipeline::PipelineLayoutBuilder::new()
+            .add_module(&module)
+            .unwrap()
+            .add_module(&sample)
+            .unwrap()
+            .add_module(&module.try_clone().unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let sets: Vec<u32> = layout.sets.iter().map(|set| set.set).collect();
+        assert_eq!(sets, vec![0, 2]);
+        let set_0: Vec<u32> = layout.sets[0]
+            .bindings
+            .iter()
+            .map(|binding| binding.binding.binding)
+            .collect();
+        assert_eq!(set_0, vec![0, 1]);
+        for set in &layout.sets {
+            for binding in &set.bindings {
+                assert_eq!(
+                    binding.stage_flags,
+                    types::ReflectShaderStageFlags::FRAGMENT
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn pipeline_layout_conflicting_modules() {
+        let ps_data = include_bytes!("./ImGuiPs.spirv");
+        let mut module = ShaderModule::load_u8_data(ps_data).unwrap();
+        let bindings = module.enumerate_descriptor_bindings(None).unwrap();
+        let tex_descriptor = bindings
+            .iter()
+            .find(|binding| binding.name == "tex")
+            .unwrap();
+        module
+            .change_descriptor_binding_numbers(tex_descriptor, 2, Some(2))
+            .unwrap();
+        let bindings = module.enumerate_descriptor_bindings(None).unwrap();
+        let smp_descriptor = bindings
+            .iter()
+            .find(|binding| binding.name == "smp")
+            .unwrap();
+        module
+            .change_descriptor_binding_numbers(smp_descriptor, 1, None)
+            .unwrap();
+
+        let sample_data = include_bytes!("../examples/sample.spv");
+        let sample = ShaderModule::load_u8_data(sample_data).unwrap();
+
+        let mut builder = pipeline::PipelineLayoutBuilder::new();
+        builder.add_module(&sample).unwrap();
+        builder.add_module(&module).unwrap();
+        assert_eq!(
+            builder.conflicts(),
+            &[pipeline::PipelineLayoutConflict::DescriptorType {
+                set: 2,
+                binding: 2,
+                stage_flags: types::ReflectShaderStageFlags::FRAGMENT,
+                existing: types::ReflectDescriptorType::UniformBuffer,
+                conflicting: types::ReflectDescriptorType::SampledImage,
+            }]
+        );
+    }
+
+    #[test]
+    fn vertex_input_layout_interleaved() {
+        let sample_data = include_bytes!("../examples/sample.spv");
+        let module = ShaderModule::load_u8_data(sample_data).unwrap();
+        let input_vars = module.enumerate_input_variables(None).unwrap();
+
+        let layout = vertex::derive_vertex_input_layout(
+            &input_vars,
+            vertex::VertexBufferStrategy::Interleaved,
+        );
+        let attributes: Vec<(&str, u32, u32)> = layout
+            .attributes
+            .iter()
+            .map(|attribute| {
+                (
+                    attribute.name.as_str(),
+                    attribute.location,
+                    attribute.offset,
+                )
+            })
+            .collect();
+        assert_eq!(
+            attributes,
+            vec![
+                ("input.Normal", 0, 0),
+                ("input.Color", 1, 12),
+                ("input.Alpha", 2, 24),
+                ("input.Scaling", 3, 28),
+                ("input.TexCoord0", 4, 44),
+                ("input.TexCoord1", 5, 52),
+                ("input.TexCoord2", 6, 60),
+            ]
+        );
+        assert_eq!(
+            layout.attributes[3].format,
+            types::ReflectFormat::R32G32B32A32_SFLOAT
+        );
+        assert_eq!(
+            layout.buffers,
+            vec![vertex::VertexBufferLayout {
+                binding: 0,
+                stride: 68
+            }]
+        );
+    }
+
+    #[test]
+    fn format_from_numeric_traits() {
+        let numeric = |width, signedness, component_count| types::ReflectNumericTraits {
+            scalar: types::ReflectNumericTraitsScalar { width, signedness },
+            vector: types::ReflectNumericTraitsVector { component_count },
+            ..Default::default()
+        };
+        let float = types::ReflectTypeFlags::FLOAT | types::ReflectTypeFlags::VECTOR;
+        let int = types::ReflectTypeFlags::INT | types::ReflectTypeFlags::VECTOR;
+
+        assert_eq!(
+            types::ReflectFormat::from_numeric_traits(&numeric(16, 0, 4), float),
+            types::ReflectFormat::R16G16B16A16_SFLOAT
+        );
+        assert_eq!(
+            types::ReflectFormat::from_numeric_traits(
+                &numeric(64, 0, 0),
+                types::ReflectTypeFlags::FLOAT
+            ),
+            types::ReflectFormat::R64_SFLOAT
+        );
+        assert_eq!(
+            types::ReflectFormat::from_numeric_traits(&numeric(8, 1, 2), int),
+            types::ReflectFormat::R8G8_SINT
+        );
+        assert_eq!(
+            types::ReflectFormat::from_numeric_traits(&numeric(16, 0, 3), int),
+            types::ReflectFormat::R16G16B16_UINT
+        );
+        assert_eq!(
+            types::ReflectFormat::from_numeric_traits(
+                &numeric(32, 0, 2),
+                types::ReflectTypeFlags::BOOL
+            ),
+            types::ReflectFormat::Undefined
+        );
+    }
+
+    // Output of `codegen::generate_block_structs` for MyConstants in examples/sample.spv, compiled
+    // so that its `Pod` impls and size assertions are checked. Type names keep the shader's casing.
+    #[allow(dead_code, clippy::upper_case_acronyms)]
+    mod my_constants {
+        include!("codegen/my_constants.rs");
+    }
+
+    #[test]
+    fn generate_block_structs() {
+        let module = ShaderModule::load_u8_data(include_bytes!("../examples/sample.spv")).unwrap();
+        let bindings = module.enumerate_descriptor_bindings(None).unwrap();
+        let constants = bindings
+            .iter()
+            .find(|binding| binding.name == "MyConstants")
+            .unwrap();
+
+        let source = codegen::generate_block_structs(&constants.block, &constants.name).unwrap();
+        assert_eq!(source, include_str!("codegen/my_constants.rs"));
+
+        // The compiled structs place every member at its reflected offset.
+        use my_constants::MyConstants;
+        let offset = |index: usize| constants.block.members[index].offset as usize;
+        assert_eq!(std::mem::offset_of!(MyConstants, xform_matrix), offset(0));
+        assert_eq!(std::mem::offset_of!(MyConstants, scale), offset(1));
+        assert_eq!(std::mem::offset_of!(MyConstants, rgb), offset(2));
+        assert_eq!(std::mem::offset_of!(MyConstants, t), offset(3));
+        assert_eq!(std::mem::offset_of!(MyConstants, uv), offset(4));
+        let value: MyConstants = bytemuck::Zeroable::zeroed();
+        assert_eq!(
+            bytemuck::bytes_of(&value).len(),
+            constants.block.size as usize
+        );
+    }
+
+    #[test]
+    fn check_module_layouts() {
+        let module = ShaderModule::load_u8_data(include_bytes!("../examples/sample.spv")).unwrap();
+        let reports = layout::check_module_layouts(&module).unwrap();
+        let constants = reports
+            .iter()
+            .find(|report| report.name == "MyConstants")
+            .unwrap();
+        assert_eq!(constants.set, Some(2));
+        // The HLSL cbuffer packs the float2 `uv` right after the float `t`.
+        let uv = layout::LayoutViolation {
+            path: "uv".to_owned(),
+            kind: layout::LayoutViolationKind::Offset,
+            expected: 104,
+            actual: 100,
+        };
+        assert_eq!(constants.std140, vec![uv.clone()]);
+        assert_eq!(constants.std430, vec![uv]);
+        assert_eq!(
+            constants.strictest_layout(),
+            Some(layout::BlockLayout::Scalar)
+        );
+        assert!(reports.iter().any(|report| report.name == "MyBufferIn"));
+    }
+
+    #[cfg(feature = "ash")]
+    #[test]
+    fn ash_descriptor_set_layout_bindings() {
+        use ash::vk;
+
+        let module = ShaderModule::load_u8_data(include_bytes!("../examples/sample.spv")).unwrap();
+        let stage_flags = module.get_shader_stage();
+        let sets = module.enumerate_descriptor_sets(None).unwrap();
+        let set = sets.iter().find(|set| set.set == 2).unwrap();
+        let bindings = vulkan::descriptor_set_layout_bindings(set, stage_flags).unwrap();
+        assert_eq!(bindings.len(), set.bindings.len());
+        for (vk_binding, binding) in bindings.iter().zip(&set.bindings) {
+            assert_eq!(vk_binding.binding, binding.binding);
+            assert_eq!(vk_binding.descriptor_count, binding.count);
+            assert_eq!(vk_binding.stage_flags, vk::ShaderStageFlags::FRAGMENT);
+        }
+        let constants = bindings
+            .iter()
+            .zip(&set.bindings)
+            .find(|(_, binding)| binding.name == "MyConstants")
+            .unwrap()
+            .0;
+        assert_eq!(
+            constants.descriptor_type,
+            vk::DescriptorType::UNIFORM_BUFFER
+        );
+
+        let inputs = module.enumerate_input_variables(None).unwrap();
+        let formats: Vec<vk::Format> = inputs.iter().map(|input| input.format.into()).collect();
+        assert!(formats.contains(&vk::Format::R32G32B32A32_SFLOAT));
+    }
+
+    #[cfg(feature = "wgpu")]
+    #[test]
+    fn wgpu_bind_group_layout_entries() {
+        use std::num::NonZeroU64;
+
+        let module = ShaderModule::load_u8_data(include_bytes!("./ImGuiPs.spirv")).unwrap();
+        let sets = module.enumerate_descriptor_sets(None).unwrap();
+        let entries =
+            webgpu::bind_group_layout_entries(&sets[0], module.get_shader_stage()).unwrap();
+        assert!(entries.iter().any(|entry| entry.ty
+            == wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            }));
+        assert!(entries.iter().any(
+            |entry| entry.ty == wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering)
+        ));
+
+        let module = ShaderModule::load_u8_data(include_bytes!("../examples/sample.spv")).unwrap();
+        let mut builder = pipeline::PipelineLayoutBuilder::new();
+        builder.add_module(&module).unwrap();
+        let layout = builder.build().unwrap();
+        let entries = webgpu::pipeline_layout_set_entries(&layout.sets[0]).unwrap();
+        let constants = entries.iter().find(|entry| entry.binding == 2).unwrap();
+        assert_eq!(constants.visibility, wgpu::ShaderStages::FRAGMENT);
+        assert_eq!(
+            constants.ty,
+            wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: NonZeroU64::new(112),
+            }
+        );
+        let buffer_in = entries.iter().find(|entry| entry.binding == 3).unwrap();
+        assert_eq!(
+            buffer_in.ty,
+            wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            }
+        );
+    }
+
+    #[test]
+    fn remap_bindings() {
+        use spirv_reflect::remap::*;
+
+        let spv_data = include_bytes!("../examples/sample.spv");
+        let mut module = ShaderModule::load_u8_data(spv_data).unwrap();
+        let mut table = RemapTable::new();
+        table
+            .by_set(2, RemapTarget::set(0))
+            .by_name("MyConstants", RemapTarget::new(1, 0))
+            .by_binding(2, 3, RemapTarget::new(0, 4))
+            .by_binding(2, 4, RemapTarget::new(0, 3));
+        let report = module.remap_bindings(&table).unwrap();
+        assert!(report.unmatched.is_empty());
+        assert_eq!(report.remapped.len(), 3);
+
+        let numbers = |module: &ShaderModule| {
+            let mut numbers: Vec<(String, u32, u32)> = module
+                .enumerate_descriptor_bindings(None)
+                .unwrap()
+                .into_iter()
+                .map(|binding| (binding.name, binding.set, binding.binding))
+                .collect();
+            numbers.sort();
+            numbers
+        };
+        assert_eq!(
+            numbers(&module),
+            vec![
+                ("MyBufferIn".to_owned(), 0, 4),
+                ("MyBufferOut".to_owned(), 0, 3),
+                ("MyConstants".to_owned(), 1, 0),
+            ]
+        );
+
+        // A collision leaves the module untouched.
+        let mut module = ShaderModule::load_u8_data(spv_data).unwrap();
+        let before = numbers(&module);
+        let mut table = RemapTable::new();
+        table.by_name("MyBufferIn", RemapTarget::new(2, 4));
+        match module.remap_bindings(&table) {
+            Err(RemapError::Collisions(collisions)) => assert_eq!(
+                collisions,
+                vec![RemapCollision {
+                    set: 2,
+                    binding: 4,
+                    names: vec!["MyBufferIn".to_owned(), "MyBufferOut".to_owned()],
+                }]
+            ),
+            result => panic!("expected a collision, got {:?}", result),
+        }
+        assert_eq!(numbers(&module), before);
+
+        let report = module.remap_bindings(&RemapTable::new()).unwrap();
+        assert_eq!(report.unmatched.len(), 3);
+    }
+
+    #[test]
+    fn check_stage_interface() {
+        use spirv_reflect::interface::*;
+
+        let vertex = ShaderModule::load_u32_data(&interface_module(
+            0,
+            3,
+            &[("vs_uv", 0, 2, false), ("vs_color", 1, 4, true)],
+        ))
+        .unwrap();
+        let fragment = ShaderModule::load_u32_data(&interface_module(
+            4,
+            1,
+            &[
+                ("fs_uv", 0, 3, false),
+                ("fs_color", 1, 4, true),
+                ("fs_normal", 2, 3, false),
+            ],
+        ))
+        .unwrap();
+
+        let report =
+            interface::check_stage_interface(&vertex, None, &fragment, Some("main")).unwrap();
+        assert_eq!(
+            report.issues,
+            vec![
+                InterfaceIssue::FormatMismatch {
+                    location: 0,
+                    output: "vs_uv".to_owned(),
+                    input: "fs_uv".to_owned(),
+                    output_format: types::ReflectFormat::R32G32_SFLOAT,
+                    input_format: types::ReflectFormat::R32G32B32_SFLOAT,
+                },
+                InterfaceIssue::MissingOutput {
+                    location: 2,
+                    input: "fs_normal".to_owned(),
+                },
+            ]
+        );
+        assert!(!report.is_compatible());
+
+        let report = interface::check_stage_interface(&vertex, None, &vertex, None).unwrap();
+        assert!(report.is_compatible());
+        assert_eq!(report.issues.len(), 2);
+
+        assert!(matches!(
+            interface::check_stage_interface(&vertex, Some("missing"), &fragment, None),
+            Err(ref err) if err.kind() == error::ReflectErrorKind::ElementNotFound
+        ));
+    }
+
+    #[test]
+    fn interface_variable_order() {
+        let mut code = interface_module(
+            0,
+            3,
+            &[
+                ("position", 0, 4, false),
+                ("uv", 1, 2, false),
+                ("color", 2, 4, false),
+            ],
+        );
+        // Turn `uv` into an input, between the two outputs.
+        let pointer = code
+            .windows(4)
+            .position(|words| words == [0x0004_0020, 12, 3, 4])
+            .unwrap();
+        code[pointer + 2] = 1; // %12 = OpTypePointer Input %4
+        let variable = code
+            .windows(4)
+            .position(|words| words == [0x0004_003B, 12, 13, 3])
+            .unwrap();
+        code[variable + 3] = 1; // %13 = OpVariable %12 Input
+                                // A second vertex entry point that declares `color` before `uv`.
+        let mut second = vec![0, 100];
+        second.extend(spirv_string("second"));
+        second.extend(&[15, 13]);
+        let entry_point = 10 + (code[10] >> 16) as usize;
+        code.splice(
+            entry_point..entry_point,
+            spirv_instruction(15, &second), // OpEntryPoint
+        );
+        let module = ShaderModule::load_u32_data(&code).unwrap();
+
+        let names: Vec<String> = module
+            .enumerate_interface_variables(Some("main"))
+            .unwrap()
+            .into_iter()
+            .map(|variable| variable.name)
+            .collect();
+        assert_eq!(names, ["position", "uv", "color"]);
+        let names: Vec<String> = module
+            .enumerate_interface_variables(None)
+            .unwrap()
+            .into_iter()
+            .map(|variable| variable.name)
+            .collect();
+        assert_eq!(names, ["position", "uv", "color"]);
+        let names: Vec<String> = module
+            .enumerate_interface_variables(Some("second"))
+            .unwrap()
+            .into_iter()
+            .map(|variable| variable.name)
+            .collect();
+        assert_eq!(names, ["color", "uv"]);
+        assert_eq!(
+            module.enumerate_input_variables(None).unwrap()[0].name,
+            "uv"
+        );
+    }
+
+    #[test]
+    fn interface_variable_lookup() {
+        let mut code = interface_module(
+            0,
+            3,
+            &[
+                ("uv", 1, 2, false),
+                ("fog", 1, 1, false),
+                ("color", 2, 4, false),
+            ],
+        );
+        // Pack `fog` into the third component of location 1 and give `uv` an HLSL semantic.
+        let mut decorations = spirv_instruction(71, &[13, 31, 2]); // OpDecorate %13 Component 2
+        let mut semantic = vec![11, 5635];
+        semantic.extend(spirv_string("TEXCOORD0"));
+        decorations.extend(spirv_instruction(5632, &semantic)); // OpDecorateString UserSemantic
+        let types = code
+            .windows(2)
+            .position(|words| words == [0x0002_0013, 1])
+            .unwrap();
+        code.splice(types..types, decorations);
+        let module = ShaderModule::load_u32_data(&code).unwrap();
+
+        let variables = module.enumerate_interface_variables(None).unwrap();
+        assert_eq!(variables.len(), 3);
+        let fog = variables.iter().find(|var| var.name == "fog").unwrap();
+        assert_eq!((fog.location, fog.component), (1, 2));
+
+        let uv = module
+            .get_output_variable_by_semantic(Some("main"), "TEXCOORD0")
+            .unwrap();
+        assert_eq!((uv.name.as_str(), uv.location), ("uv", 1));
+        assert_eq!(
+            module
+                .get_output_variable_by_location(None, 1)
+                .unwrap()
+                .name,
+            "uv"
+        );
+        assert_eq!(
+            module
+                .get_output_variable_by_location(Some("main"), 2)
+                .unwrap()
+                .name,
+            "color"
+        );
+        assert!(matches!(
+            module.get_input_variable_by_location(None, 1),
+            Err(ref err) if err.kind() == error::ReflectErrorKind::ElementNotFound
+        ));
+        assert!(matches!(
+            module.get_output_variable_by_semantic(None, "COLOR0"),
+            Err(ref err) if err.kind() == error::ReflectErrorKind::ElementNotFound
+        ));
+        assert!(matches!(
+            module.get_output_variable_by_semantic(Some("main"), "TEX\0COORD0"),
+            Err(ref err) if err.kind() == error::ReflectErrorKind::ElementNotFound
+        ));
+        assert!(matches!(
+            module.get_output_variable_by_location(Some("ma\0in"), 1),
+            Err(ref err) if err.kind() == error::ReflectErrorKind::ElementNotFound
+        ));
+        assert!(module
+            .get_output_variable_by_location(Some("other"), 1)
+            .is_err());
+    }
+
+    #[test]
+    fn accessed_descriptor_bindings() {
+        let module = ShaderModule::load_u32_data(&sampler_module()).unwrap();
+        let bindings = module.enumerate_descriptor_bindings(None).unwrap();
+        let accessed: Vec<(&str, bool)> = bindings
+            .iter()
+            .map(|binding| (binding.name.as_str(), binding.accessed))
+            .collect();
+        assert_eq!(accessed, vec![("used", true), ("unused", false)]);
+
+        for entry_point in &[None, Some("main")] {
+            let bindings = module
+                .enumerate_accessed_descriptor_bindings(*entry_point)
+                .unwrap();
+            assert_eq!(bindings.len(), 1);
+            assert_eq!(bindings[0].name, "used");
+            let sets = module
+                .enumerate_accessed_descriptor_sets(*entry_point)
+                .unwrap();
+            assert_eq!(sets.len(), 1);
+            assert_eq!(sets[0].bindings.len(), 1);
+        }
+
+        let sample_data = include_bytes!("../examples/sample.spv");
+        let module = ShaderModule::load_u8_data(sample_data).unwrap();
+        let bindings = module.enumerate_accessed_descriptor_bindings(None).unwrap();
+        assert_eq!(bindings.len(), 3);
+        let constants = &bindings[0].block;
+        let unused: Vec<&str> = constants
+            .members
+            .iter()
+            .filter(|member| member.flags.contains(types::ReflectVariableFlags::UNUSED))
+            .map(|member| member.name.as_str())
+            .collect();
+        assert_eq!(unused, vec!["Rgb", "t", "uv"]);
+        assert!(constants.members[2]
+            .members
+            .iter()
+            .all(|member| member.flags.contains(types::ReflectVariableFlags::UNUSED)));
+    }
+
+    #[test]
+    fn byte_address_buffer_offsets() {
+        let module = ShaderModule::load_u32_data(&byte_address_buffer_module()).unwrap();
+        let bindings = module.enumerate_descriptor_bindings(None).unwrap();
+        assert_eq!(bindings.len(), 1);
+        assert_eq!(
+            bindings[0].descriptor_type,
+            types::ReflectDescriptorType::StorageBuffer
+        );
+        assert_eq!(
+            bindings[0].user_type,
+            types::ReflectUserType::RwByteAddressBuffer
+        );
+        assert_eq!(bindings[0].byte_address_buffer_offsets, vec![16, 20]);
+
+        let sample_data = include_bytes!("../examples/sample.spv");
+        let module = ShaderModule::load_u8_data(sample_data).unwrap();
+        for binding in module.enumerate_descriptor_bindings(None).unwrap() {
+            assert_eq!(binding.user_type, types::ReflectUserType::Invalid);
+            assert!(binding.byte_address_buffer_offsets.is_empty());
+        }
+    }
+
+    #[test]
+    fn descriptor_access() {
+        let read_write = types::ReflectAccess {
+            read: true,
+            write: true,
+        };
+        let read_only = types::ReflectAccess {
+            read: true,
+            write: false,
+        };
+
+        let mut code = byte_address_buffer_module();
+        let module = ShaderModule::load_u32_data(&code).unwrap();
+        let bindings = module.enumerate_descriptor_bindings(None).unwrap();
+        assert_eq!(bindings[0].access, read_write);
+
+        // A `readonly` buffer has its members decorated NonWritable.
+        let types = code
+            .windows(2)
+            .position(|words| words == [0x0002_0013, 1])
+            .unwrap();
+        let non_writable = spirv_instruction(72, &[5, 0, 24]); // OpMemberDecorate NonWritable
+        code.splice(types..types, non_writable);
+        let module = ShaderModule::load_u32_data(&code).unwrap();
+        let bindings = module.enumerate_descriptor_bindings(None).unwrap();
+        assert_eq!(bindings[0].access, read_only);
+
+        let sample_data = include_bytes!("../examples/sample.spv");
+        let module = ShaderModule::load_u8_data(sample_data).unwrap();
+        let access: Vec<(String, types::ReflectAccess)> = module
+            .enumerate_descriptor_bindings(None)
+            .unwrap()
+            .into_iter()
+            .map(|binding| (binding.name, binding.access))
+            .collect();
+        assert_eq!(
+            access,
+            vec![
+                ("MyConstants".to_owned(), read_only),
+                ("MyBufferIn".to_owned(), read_write),
+                ("MyBufferOut".to_owned(), read_write),
+            ]
+        );
+    }
+
+    #[test]
+    fn block_writer_sample_constants() {
+        let module = ShaderModule::load_u8_data(include_bytes!("../examples/sample.spv")).unwrap();
+        let bindings = module.enumerate_descriptor_bindings(None).unwrap();
+        let constants = bindings
+            .iter()
+            .find(|binding| binding.name == "MyConstants")
+            .unwrap();
+
+        let mut buffer = vec![0u8; constants.block.size as usize];
+        let mut writer = writer::BlockWriter::new(&constants.block, &mut buffer);
+        let columns = [
+            [0.0f32, 1.0, 2.0, 3.0],
+            [4.0, 5.0, 6.0, 7.0],
+            [8.0, 9.0, 10.0, 11.0],
+            [12.0, 13.0, 14.0, 15.0],
+        ];
+        writer.set("XformMatrix", columns).unwrap();
+        writer.set("Rgb.b", 0.25).unwrap();
+        writer.set("uv", [0.5, 0.75]).unwrap();
+
+        let read = |offset: usize| {
+            f32::from_le_bytes([
+                buffer[offset],
+                buffer[offset + 1],
+                buffer[offset + 2],
+                buffer[offset + 3],
+            ])
+        };
+        // XformMatrix is decorated RowMajor, so each row is contiguous.
+        for (column, values) in columns.iter().enumerate() {
+            for (row, &value) in values.iter().enumerate() {
+                assert_eq!(read(row * 16 + column * 4), value);
+            }
+        }
+        assert_eq!(read(88), 0.25);
+        assert_eq!(read(100), 0.5);
+        assert_eq!(read(104), 0.75);
+    }
+
+    fn assert_round_trip<T>(value: &T)
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + std::fmt::Debug,
+    {
+        let yaml = serde_yaml::to_string(value).unwrap();
+        assert_eq!(&serde_yaml::from_str::<T>(&yaml).unwrap(), value);
+        let json = serde_json::to_string(value).unwrap();
+        assert_eq!(&serde_json::from_str::<T>(&json).unwrap(), value);
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        for spv_data in &[
+            &include_bytes!("./ImGuiPs.spirv")[..],
+            &include_bytes!("../examples/sample.spv")[..],
+        ] {
+            let module = ShaderModule::load_u8_data(spv_data).unwrap();
+            assert_round_trip(&module.enumerate_entry_points().unwrap());
+            assert_round_trip(&module.enumerate_capabilities().unwrap());
+            assert_round_trip(&module.enumerate_descriptor_bindings(None).unwrap());
+            assert_round_trip(&module.enumerate_descriptor_sets(None).unwrap());
+            assert_round_trip(&module.enumerate_push_constant_blocks(None).unwrap());
+            assert_round_trip(&module.enumerate_input_variables(None).unwrap());
+            assert_round_trip(&module.enumerate_output_variables(None).unwrap());
+            assert_round_trip(&module.get_generator());
+        }
+
+        let module = ShaderModule::load_u32_data(&spec_constant_module()).unwrap();
+        assert_round_trip(&module.enumerate_specialization_constants().unwrap());
+    }
+
+    #[cfg(feature = "c-backend")]
+    #[test]
+    fn borrowed_module_matches_owned_module() {
+        let ps_data = include_bytes!("./ImGuiPs.spirv");
+        let code: Vec<u32> = ps_data
+            .chunks(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .collect();
+        let owned = ShaderModule::load_u32_data(&code).unwrap();
+        let borrowed = ShaderModuleRef::load_u32_data(&code).unwrap();
+
+        assert_eq!(borrowed.get_code(), code);
+        assert_eq!(
+            borrowed.enumerate_descriptor_bindings(None).unwrap(),
+            owned.enumerate_descriptor_bindings(None).unwrap()
+        );
+        assert_eq!(
+            borrowed.enumerate_entry_points().unwrap()[0].name,
+            owned.get_entry_point_name()
+        );
+
+        let mut cloned = borrowed.try_clone().unwrap();
+        let binding = cloned.enumerate_descriptor_bindings(None).unwrap()[0].clone();
+        cloned
+            .change_descriptor_binding_numbers(&binding, 7, None)
+            .unwrap();
+        assert_eq!(borrowed.get_code(), code);
+    }
+
+    #[cfg(feature = "pure-rust")]
+    #[test]
+    fn pure_block_size_overflow() {
+        // Rounding the end of a member at the top of the address space up to 16 bytes overflows.
+        let mut code = byte_address_buffer_module();
+        let offset = code
+            .windows(4)
+            .position(|words| words == [5, 0, 35, 0])
+            .unwrap();
+        code[offset + 3] = u32::MAX - 1; // OpMemberDecorate %5 0 Offset 0xFFFFFFFE
+        match pure::ShaderModule::load_u32_data(&code) {
+            Err(ref err) if err.kind() == error::ReflectErrorKind::RangeExceeded => {}
+            Err(err) => panic!("unexpected error: {}", err),
+            Ok(_) => panic!("block with an overflowing size was accepted"),
+        }
+    }
+
+    #[cfg(feature = "pure-rust")]
+    #[test]
+    fn pure_load_invalid_code_size() {
+        match pure::create_shader_module(&[0x03, 0x02, 0x23, 0x07, 0x00, 0x00]) {
+            Err(ref err) if err.kind() == error::ReflectErrorKind::SpirvInvalidCodeSize => {}
+            Err(err) => panic!("unexpected error: {}", err),
+            Ok(_) => panic!("module with a truncated word was accepted"),
+        }
+    }
+
+    #[cfg(all(feature = "c-backend", feature = "pure-rust"))]
+    fn assert_blocks_match(c: &types::ReflectBlockVariable, pure: &types::ReflectBlockVariable) {
+        assert_eq!(c.name, pure.name);
+        assert_eq!(c.offset, pure.offset, "{}", c.name);
+        assert_eq!(c.absolute_offset, pure.absolute_offset, "{}", c.name);
+        assert_eq!(c.size, pure.size, "{}", c.name);
+        assert_eq!(c.padded_size, pure.padded_size, "{}", c.name);
+        assert_eq!(c.members.len(), pure.members.len(), "{}", c.name);
+        for (c_member, pure_member) in c.members.iter().zip(&pure.members) {
+            assert_blocks_match(c_member, pure_member);
+        }
+    }
+
+    #[cfg(all(feature = "c-backend", feature = "pure-rust"))]
+    fn assert_variables_match(
+        c: &[types::ReflectInterfaceVariable],
+        pure: &[types::ReflectInterfaceVariable],
+    ) {
+        assert_eq!(c.len(), pure.len());
+        for (c_var, pure_var) in c.iter().zip(pure) {
+            assert_eq!(c_var.name, pure_var.name);
+            assert_eq!(c_var.location, pure_var.location, "{}", c_var.name);
+            assert_eq!(
+                c_var.storage_class, pure_var.storage_class,
+                "{}",
+                c_var.name
+            );
+            assert_eq!(
+                c_var.decoration_flags, pure_var.decoration_flags,
+                "{}",
+                c_var.name
+            );
+            assert_eq!(c_var.numeric, pure_var.numeric, "{}", c_var.name);
+            assert_eq!(c_var.format, pure_var.format, "{}", c_var.name);
+            assert_eq!(c_var.word_offset, pure_var.word_offset, "{}", c_var.name);
+        }
+    }
+
+    #[cfg(all(feature = "c-backend", feature = "pure-rust"))]
+    fn assert_backends_match(spv_data: &[u8]) {
+        let c = ShaderModule::load_u8_data(spv_data).unwrap();
+        let pure = pure::ShaderModule::load_u8_data(spv_data).unwrap();
+
+        assert_eq!(c.get_code(), pure.get_code());
+        assert_eq!(c.get_generator(), pure.get_generator());
+        assert_eq!(c.get_shader_stage(), pure.get_shader_stage());
+        assert_eq!(c.get_entry_point_name(), pure.get_entry_point_name());
+        assert_eq!(
+            c.enumerate_capabilities().unwrap(),
+            pure.enumerate_capabilities().unwrap()
+        );
+        assert_eq!(
+            c.enumerate_specialization_constants().unwrap(),
+            pure.enumerate_specialization_constants().unwrap()
+        );
+
+        let c_entry_points = c.enumerate_entry_points().unwrap();
+        let pure_entry_points = pure.enumerate_entry_points().unwrap();
+        assert_eq!(c_entry_points.len(), pure_entry_points.len());
+        for (c_entry, pure_entry) in c_entry_points.iter().zip(&pure_entry_points) {
+            assert_eq!(c_entry.name, pure_entry.name);
+            assert_eq!(c_entry.id, pure_entry.id);
+            assert_eq!(c_entry.shader_stage, pure_entry.shader_stage);
+            assert_eq!(c_entry.used_uniforms, pure_entry.used_uniforms);
+            assert_eq!(c_entry.used_push_constants, pure_entry.used_push_constants);
+            assert_eq!(c_entry.execution_modes, pure_entry.execution_modes);
+        }
+
+        let c_bindings = c.enumerate_descriptor_bindings(None).unwrap();
+        let pure_bindings = pure.enumerate_descriptor_bindings(None).unwrap();
+        assert_eq!(c_bindings.len(), pure_bindings.len());
+        for (c_binding, pure_binding) in c_bindings.iter().zip(&pure_bindings) {
+            assert_eq!(c_binding.name, pure_binding.name);
+            assert_eq!(c_binding.set, pure_binding.set);
+            assert_eq!(c_binding.binding, pure_binding.binding);
+            assert_eq!(c_binding.descriptor_type, pure_binding.descriptor_type);
+            assert_eq!(c_binding.resource_type, pure_binding.resource_type);
+            assert_eq!(c_binding.count, pure_binding.count);
+            assert_eq!(c_binding.image, pure_binding.image);
+            assert_eq!(c_binding.word_offset, pure_binding.word_offset);
+            assert_blocks_match(&c_binding.block, &pure_binding.block);
+        }
+
+        let c_sets = c.enumerate_descriptor_sets(None).unwrap();
+        let pure_sets = pure.enumerate_descriptor_sets(None).unwrap();
+        assert_eq!(
+            c_sets.iter().map(|set| set.set).collect::<Vec<_>>(),
+            pure_sets.iter().map(|set| set.set).collect::<Vec<_>>()
+        );
+
+        let c_blocks = c.enumerate_push_constant_blocks(None).unwrap();
+        let pure_blocks = pure.enumerate_push_constant_blocks(None).unwrap();
+        assert_eq!(c_blocks.len(), pure_blocks.len());
+        for (c_block, pure_block) in c_blocks.iter().zip(&pure_blocks) {
+            assert_blocks_match(c_block, pure_block);
+        }
+
+        assert_variables_match(
+            &c.enumerate_input_variables(None).unwrap(),
+            &pure.enumerate_input_variables(None).unwrap(),
+        );
+        assert_variables_match(
+            &c.enumerate_output_variables(None).unwrap(),
+            &pure.enumerate_output_variables(None).unwrap(),
+        );
+    }
+
+    #[cfg(all(feature = "c-backend", feature = "pure-rust"))]
+    #[test]
+    fn pure_backend_matches_c_backend() {
+        assert_backends_match(include_bytes!("./ImGuiPs.spirv"));
+        assert_backends_match(include_bytes!("../examples/sample.spv"));
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn cli_json_output() {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_spirv-reflect"))
+            .args(["--format", "json", "--stage", "ps", "tests/ImGuiPs.spirv"])
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("\"entry_point_name\": \"ImGuiPs\""));
+        assert!(stdout.contains("\"descriptor_binding_count\": 2"));
+
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_spirv-reflect"))
+            .args(["--stage", "vs", "tests/ImGuiPs.spirv"])
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert!(output.stdout.iter().all(|byte| byte.is_ascii_whitespace()));
+
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_spirv-reflect"))
+            .args(["--entry-point", "missing", "tests/ImGuiPs.spirv"])
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .output()
             .unwrap();
+        assert_eq!(output.status.code(), Some(1));
     }
 }